@@ -0,0 +1,55 @@
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+
+/// Prefix of a string holding base64 encoded bytes, as in `base64:aGVsbG8=`.
+pub(crate) const BASE64_PREFIX: &str = "base64:";
+
+/// Prefix of a string holding hex encoded bytes, as in `hex:68656c6c6f`.
+pub(crate) const HEX_PREFIX: &str = "hex:";
+
+/// Decodes a string carrying an explicit `base64:` or `hex:` prefix.
+///
+/// Returns `None` for strings without a prefix, and `Some(Err(()))` for malformed encodings.
+pub(crate) fn decode_prefixed(text: &str) -> Option<Result<Vec<u8>, ()>> {
+    if let Some(encoded) = text.strip_prefix(BASE64_PREFIX) {
+        Some(decode_base64(encoded).ok_or(()))
+    } else {
+        text.strip_prefix(HEX_PREFIX)
+            .map(|encoded| decode_hex(encoded).ok_or(()))
+    }
+}
+
+/// Decodes base64 with either the standard or the URL-safe alphabet, with or without padding.
+///
+/// Whitespace is ignored so line-wrapped encodings, such as the body of a PEM file, are accepted.
+pub(crate) fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let encoded: String = encoded.chars().filter(|c| !c.is_whitespace()).collect();
+
+    [STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD]
+        .iter()
+        .find_map(|engine| engine.decode(&encoded).ok())
+}
+
+pub(crate) fn encode_base64(bytes: &[u8]) -> String {
+    STANDARD.encode(bytes)
+}
+
+/// Decodes pairs of hex digits in either case, ignoring whitespace between them.
+pub(crate) fn decode_hex(encoded: &str) -> Option<Vec<u8>> {
+    let digits = encoded
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<u8>>>()?;
+
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+
+    Some(
+        digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect(),
+    )
+}
@@ -199,6 +199,11 @@ impl Config {
             .and_then(|value| value.into_array().map_err(|e| e.extend_with_key(key)))
     }
 
+    pub fn get_bytes(&self, key: &str) -> Result<Vec<u8>> {
+        self.get_value(key)
+            .and_then(|value| value.into_bytes().map_err(|e| e.extend_with_key(key)))
+    }
+
     /// Attempt to deserialize the entire configuration into the requested type.
     pub fn try_deserialize<'de, T: Deserialize<'de>>(self) -> Result<T> {
         T::deserialize(self)
@@ -0,0 +1,767 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de;
+use serde::ser;
+
+/// Name of the newtype a [`Datetime`] (de)serializes as, letting the configuration serializer
+/// keep it as [`ValueKind::Datetime`](crate::ValueKind::Datetime) instead of a string.
+pub(crate) const DATETIME_NAME: &str = "$__config_private_Datetime";
+
+/// A date, a time or both, optionally with an offset from UTC.
+///
+/// This covers the four kinds of temporal values TOML knows about: offset datetimes, local
+/// datetimes, local dates and local times. It is read from and displayed as RFC 3339, e.g.
+/// `1979-05-27T07:32:00-08:00`, `1979-05-27T07:32:00`, `1979-05-27` or `07:32:00.999`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Datetime {
+    /// The date, absent for a local time.
+    pub date: Option<Date>,
+
+    /// The time, absent for a local date.
+    pub time: Option<Time>,
+
+    /// The offset from UTC, absent for local values.
+    pub offset: Option<Offset>,
+}
+
+/// A calendar date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// A time of day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub nanosecond: u32,
+}
+
+/// An offset from UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Offset {
+    /// UTC, written as `Z`.
+    Z,
+
+    /// An offset in minutes, e.g. `-480` for `-08:00`.
+    Custom { minutes: i16 },
+}
+
+impl fmt::Display for Datetime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref date) = self.date {
+            write!(f, "{}", date)?;
+        }
+
+        if let Some(ref time) = self.time {
+            if self.date.is_some() {
+                write!(f, "T")?;
+            }
+            write!(f, "{}", time)?;
+        }
+
+        if let Some(ref offset) = self.offset {
+            write!(f, "{}", offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+
+        if self.nanosecond != 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Offset::Z => write!(f, "Z"),
+            Offset::Custom { minutes } => {
+                let sign = if minutes < 0 { '-' } else { '+' };
+                let minutes = i32::from(minutes).abs();
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+impl FromStr for Datetime {
+    type Err = DatetimeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || DatetimeParseError(s.to_owned());
+        let mut rest = s.as_bytes();
+
+        // A local time has no date, everything else starts with one
+        let date = if rest.get(2) == Some(&b':') {
+            None
+        } else {
+            let date = parse_date(&mut rest).ok_or_else(error)?;
+            match rest.first() {
+                Some(b'T' | b't' | b' ') if rest.len() > 1 => rest = &rest[1..],
+                None => {
+                    return Ok(Self {
+                        date: Some(date),
+                        time: None,
+                        offset: None,
+                    })
+                }
+                Some(_) => return Err(error()),
+            }
+            Some(date)
+        };
+
+        let time = parse_time(&mut rest).ok_or_else(error)?;
+        let offset = match rest {
+            [] => None,
+            _ if date.is_none() => return Err(error()),
+            [b'Z' | b'z'] => Some(Offset::Z),
+            [sign @ (b'+' | b'-'), offset @ ..] => {
+                let mut offset = offset;
+                let hours = parse_number(&mut offset, 2).filter(|&hours| hours < 24);
+                let minutes = match offset {
+                    [b':', ..] => {
+                        offset = &offset[1..];
+                        parse_number(&mut offset, 2).filter(|&minutes| minutes < 60)
+                    }
+                    _ => None,
+                };
+
+                match (hours, minutes, offset.is_empty()) {
+                    (Some(hours), Some(minutes), true) => {
+                        let minutes = (hours * 60 + minutes) as i16;
+                        Some(Offset::Custom {
+                            minutes: if *sign == b'-' { -minutes } else { minutes },
+                        })
+                    }
+                    _ => return Err(error()),
+                }
+            }
+            _ => return Err(error()),
+        };
+
+        Ok(Self {
+            date,
+            time: Some(time),
+            offset,
+        })
+    }
+}
+
+impl From<Date> for Datetime {
+    fn from(date: Date) -> Self {
+        Self {
+            date: Some(date),
+            time: None,
+            offset: None,
+        }
+    }
+}
+
+impl From<Time> for Datetime {
+    fn from(time: Time) -> Self {
+        Self {
+            date: None,
+            time: Some(time),
+            offset: None,
+        }
+    }
+}
+
+fn parse_date(s: &mut &[u8]) -> Option<Date> {
+    let year = parse_number(s, 4)?;
+    expect(s, b'-')?;
+    let month = parse_number(s, 2).filter(|month| (1..=12).contains(month))?;
+    expect(s, b'-')?;
+    let day = parse_number(s, 2)?;
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    };
+
+    if day == 0 || day > days {
+        return None;
+    }
+
+    Some(Date {
+        year: year as u16,
+        month: month as u8,
+        day: day as u8,
+    })
+}
+
+fn parse_time(s: &mut &[u8]) -> Option<Time> {
+    let hour = parse_number(s, 2).filter(|&hour| hour < 24)?;
+    expect(s, b':')?;
+    let minute = parse_number(s, 2).filter(|&minute| minute < 60)?;
+    expect(s, b':')?;
+    // Allow for leap seconds
+    let second = parse_number(s, 2).filter(|&second| second <= 60)?;
+
+    let mut nanosecond = 0;
+    if s.first() == Some(&b'.') {
+        *s = &s[1..];
+        let digits = s.iter().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+
+        // Anything beyond nanoseconds is truncated
+        for (i, c) in s[..digits].iter().take(9).enumerate() {
+            nanosecond += u32::from(c - b'0') * 10u32.pow(8 - i as u32);
+        }
+        *s = &s[digits..];
+    }
+
+    Some(Time {
+        hour: hour as u8,
+        minute: minute as u8,
+        second: second as u8,
+        nanosecond,
+    })
+}
+
+/// Parses exactly `digits` ASCII digits.
+fn parse_number(s: &mut &[u8], digits: usize) -> Option<u32> {
+    if s.len() < digits || !s[..digits].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+
+    let number = s[..digits]
+        .iter()
+        .fold(0, |number, c| number * 10 + u32::from(c - b'0'));
+    *s = &s[digits..];
+    Some(number)
+}
+
+fn expect(s: &mut &[u8], c: u8) -> Option<()> {
+    match s.split_first() {
+        Some((&first, rest)) if first == c => {
+            *s = rest;
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+/// Error returned when a string is not a valid [`Datetime`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatetimeParseError(String);
+
+impl fmt::Display for DatetimeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid datetime `{}`", self.0)
+    }
+}
+
+impl Error for DatetimeParseError {}
+
+impl ser::Serialize for Datetime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_newtype_struct(DATETIME_NAME, &self.to_string())
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Datetime {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct DatetimeVisitor;
+
+        impl<'de> de::Visitor<'de> for DatetimeVisitor {
+            type Value = Datetime;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an RFC 3339 datetime, date or time")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Datetime, E>
+            where
+                E: de::Error,
+            {
+                value.parse().map_err(E::custom)
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Datetime, D::Error>
+            where
+                D: de::Deserializer<'de>,
+            {
+                deserializer.deserialize_str(self)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(DATETIME_NAME, DatetimeVisitor)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl From<toml::value::Datetime> for Datetime {
+    fn from(datetime: toml::value::Datetime) -> Self {
+        Self {
+            date: datetime.date.map(|date| Date {
+                year: date.year,
+                month: date.month,
+                day: date.day,
+            }),
+            time: datetime.time.map(|time| Time {
+                hour: time.hour,
+                minute: time.minute,
+                second: time.second,
+                nanosecond: time.nanosecond,
+            }),
+            offset: datetime.offset.map(|offset| match offset {
+                toml::value::Offset::Z => Offset::Z,
+                toml::value::Offset::Custom { minutes } => Offset::Custom { minutes },
+            }),
+        }
+    }
+}
+
+/// Converts the year of a date from another library, which must be written with four digits.
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn year(year: i32) -> Result<u16, crate::ConfigError> {
+    match year {
+        0..=9999 => Ok(year as u16),
+        _ => Err(crate::ConfigError::Message(format!(
+            "year {} is out of the range of a datetime, 0 to 9999",
+            year
+        ))),
+    }
+}
+
+/// Converts an offset from UTC in seconds, which must be a whole number of minutes.
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn offset_minutes(seconds: i32) -> Result<i16, crate::ConfigError> {
+    use std::convert::TryFrom;
+
+    match i16::try_from(seconds / 60) {
+        Ok(minutes) if seconds % 60 == 0 => Ok(minutes),
+        _ => Err(crate::ConfigError::Message(format!(
+            "offset of {} seconds from UTC is not a whole number of minutes a datetime can hold",
+            seconds
+        ))),
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_support {
+    use std::convert::{TryFrom, TryInto};
+
+    use chrono::{
+        DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+    };
+
+    use super::{offset_minutes, year, Date, Datetime, Offset, Time};
+    use crate::error::{ConfigError, Unexpected};
+
+    impl TryFrom<NaiveDate> for Date {
+        type Error = ConfigError;
+
+        fn try_from(date: NaiveDate) -> Result<Self, Self::Error> {
+            Ok(Self {
+                year: year(date.year())?,
+                // Months and days of a valid date always fit
+                month: date.month() as u8,
+                day: date.day() as u8,
+            })
+        }
+    }
+
+    impl From<NaiveTime> for Time {
+        fn from(time: NaiveTime) -> Self {
+            Self {
+                hour: time.hour() as u8,
+                minute: time.minute() as u8,
+                second: time.second() as u8,
+                nanosecond: time.nanosecond(),
+            }
+        }
+    }
+
+    impl TryFrom<NaiveDate> for Datetime {
+        type Error = ConfigError;
+
+        fn try_from(date: NaiveDate) -> Result<Self, Self::Error> {
+            Date::try_from(date).map(Into::into)
+        }
+    }
+
+    impl From<NaiveTime> for Datetime {
+        fn from(time: NaiveTime) -> Self {
+            Time::from(time).into()
+        }
+    }
+
+    impl TryFrom<NaiveDateTime> for Datetime {
+        type Error = ConfigError;
+
+        fn try_from(datetime: NaiveDateTime) -> Result<Self, Self::Error> {
+            Ok(Self {
+                date: Some(datetime.date().try_into()?),
+                time: Some(datetime.time().into()),
+                offset: None,
+            })
+        }
+    }
+
+    impl<Tz: TimeZone> TryFrom<DateTime<Tz>> for Datetime {
+        type Error = ConfigError;
+
+        fn try_from(datetime: DateTime<Tz>) -> Result<Self, Self::Error> {
+            let offset = match datetime.fixed_offset().offset().local_minus_utc() {
+                0 => Offset::Z,
+                seconds => Offset::Custom {
+                    minutes: offset_minutes(seconds)?,
+                },
+            };
+
+            Ok(Self {
+                offset: Some(offset),
+                ..datetime.naive_local().try_into()?
+            })
+        }
+    }
+
+    impl TryFrom<Date> for NaiveDate {
+        type Error = ConfigError;
+
+        fn try_from(date: Date) -> Result<Self, Self::Error> {
+            NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())
+                .ok_or_else(|| invalid(date.into(), "a date"))
+        }
+    }
+
+    impl TryFrom<Time> for NaiveTime {
+        type Error = ConfigError;
+
+        fn try_from(time: Time) -> Result<Self, Self::Error> {
+            NaiveTime::from_hms_nano_opt(
+                time.hour.into(),
+                time.minute.into(),
+                time.second.into(),
+                time.nanosecond,
+            )
+            .ok_or_else(|| invalid(time.into(), "a time"))
+        }
+    }
+
+    impl TryFrom<Datetime> for NaiveDate {
+        type Error = ConfigError;
+
+        fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+            match datetime {
+                Datetime {
+                    date: Some(date),
+                    time: None,
+                    offset: None,
+                } => date.try_into(),
+                _ => Err(invalid(datetime, "a local date")),
+            }
+        }
+    }
+
+    impl TryFrom<Datetime> for NaiveTime {
+        type Error = ConfigError;
+
+        fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+            match datetime {
+                Datetime {
+                    date: None,
+                    time: Some(time),
+                    offset: None,
+                } => time.try_into(),
+                _ => Err(invalid(datetime, "a local time")),
+            }
+        }
+    }
+
+    impl TryFrom<Datetime> for NaiveDateTime {
+        type Error = ConfigError;
+
+        fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+            match datetime {
+                Datetime {
+                    date: Some(date),
+                    time: Some(time),
+                    offset: None,
+                } => Ok(NaiveDateTime::new(date.try_into()?, time.try_into()?)),
+                _ => Err(invalid(datetime, "a local datetime")),
+            }
+        }
+    }
+
+    impl TryFrom<Datetime> for DateTime<FixedOffset> {
+        type Error = ConfigError;
+
+        fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+            let seconds = match datetime.offset {
+                Some(Offset::Z) => 0,
+                Some(Offset::Custom { minutes }) => i32::from(minutes) * 60,
+                None => return Err(invalid(datetime, "an offset datetime")),
+            };
+
+            let local = NaiveDateTime::try_from(Datetime {
+                offset: None,
+                ..datetime
+            })?;
+
+            FixedOffset::east_opt(seconds)
+                .and_then(|offset| offset.from_local_datetime(&local).single())
+                .ok_or_else(|| invalid(datetime, "an offset datetime"))
+        }
+    }
+
+    fn invalid(datetime: Datetime, expected: &'static str) -> ConfigError {
+        ConfigError::invalid_type(None, Unexpected::Datetime(datetime), expected)
+    }
+}
+
+#[cfg(feature = "time")]
+pub(crate) use self::time_support::time_fields;
+
+#[cfg(feature = "time")]
+mod time_support {
+    use std::convert::{TryFrom, TryInto};
+
+    use time::{Month, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+    use super::{offset_minutes, year, Date, Datetime, Offset, Time};
+    use crate::error::{ConfigError, Unexpected};
+    use crate::value::ValueKind;
+
+    /// Returns the fields of a datetime as the `time` type deserializing from a tuple of `len`
+    /// fields expects them, unless no such type takes that many.
+    ///
+    /// Unless human-readable, `time` lays out a `Date` as its year and day of the year, a `Time`
+    /// as its hour, minute, second and nanosecond, a `PrimitiveDateTime` as both and an
+    /// `OffsetDateTime` as both followed by the hours, minutes and seconds of its offset.
+    pub(crate) fn time_fields(
+        datetime: Datetime,
+        len: usize,
+    ) -> Option<Result<Vec<ValueKind>, ConfigError>> {
+        let date_fields = |date: time::Date| {
+            vec![
+                ValueKind::I64(date.year().into()),
+                ValueKind::U64(date.ordinal().into()),
+            ]
+        };
+        let time_fields = |time: time::Time| {
+            vec![
+                ValueKind::U64(time.hour().into()),
+                ValueKind::U64(time.minute().into()),
+                ValueKind::U64(time.second().into()),
+                ValueKind::U64(time.nanosecond().into()),
+            ]
+        };
+
+        let fields = match len {
+            2 => time::Date::try_from(datetime).map(date_fields),
+            4 => time::Time::try_from(datetime).map(time_fields),
+            6 => PrimitiveDateTime::try_from(datetime).map(|datetime| {
+                let mut fields = date_fields(datetime.date());
+                fields.extend(time_fields(datetime.time()));
+                fields
+            }),
+            9 => OffsetDateTime::try_from(datetime).map(|datetime| {
+                let mut fields = date_fields(datetime.date());
+                fields.extend(time_fields(datetime.time()));
+                let (hours, minutes, seconds) = datetime.offset().as_hms();
+                fields.extend(
+                    vec![hours, minutes, seconds]
+                        .into_iter()
+                        .map(|part| ValueKind::I64(part.into())),
+                );
+                fields
+            }),
+            _ => return None,
+        };
+
+        Some(fields)
+    }
+
+    impl TryFrom<time::Date> for Date {
+        type Error = ConfigError;
+
+        fn try_from(date: time::Date) -> Result<Self, Self::Error> {
+            Ok(Self {
+                year: year(date.year())?,
+                month: date.month().into(),
+                day: date.day(),
+            })
+        }
+    }
+
+    impl From<time::Time> for Time {
+        fn from(time: time::Time) -> Self {
+            Self {
+                hour: time.hour(),
+                minute: time.minute(),
+                second: time.second(),
+                nanosecond: time.nanosecond(),
+            }
+        }
+    }
+
+    impl TryFrom<time::Date> for Datetime {
+        type Error = ConfigError;
+
+        fn try_from(date: time::Date) -> Result<Self, Self::Error> {
+            Date::try_from(date).map(Into::into)
+        }
+    }
+
+    impl From<time::Time> for Datetime {
+        fn from(time: time::Time) -> Self {
+            Time::from(time).into()
+        }
+    }
+
+    impl TryFrom<PrimitiveDateTime> for Datetime {
+        type Error = ConfigError;
+
+        fn try_from(datetime: PrimitiveDateTime) -> Result<Self, Self::Error> {
+            Ok(Self {
+                date: Some(datetime.date().try_into()?),
+                time: Some(datetime.time().into()),
+                offset: None,
+            })
+        }
+    }
+
+    impl TryFrom<OffsetDateTime> for Datetime {
+        type Error = ConfigError;
+
+        fn try_from(datetime: OffsetDateTime) -> Result<Self, Self::Error> {
+            let offset = match datetime.offset().whole_seconds() {
+                0 => Offset::Z,
+                seconds => Offset::Custom {
+                    minutes: offset_minutes(seconds)?,
+                },
+            };
+
+            Ok(Self {
+                date: Some(datetime.date().try_into()?),
+                time: Some(datetime.time().into()),
+                offset: Some(offset),
+            })
+        }
+    }
+
+    impl TryFrom<Date> for time::Date {
+        type Error = ConfigError;
+
+        fn try_from(date: Date) -> Result<Self, Self::Error> {
+            Month::try_from(date.month)
+                .and_then(|month| time::Date::from_calendar_date(date.year.into(), month, date.day))
+                .map_err(|_| invalid(date.into(), "a date"))
+        }
+    }
+
+    impl TryFrom<Time> for time::Time {
+        type Error = ConfigError;
+
+        fn try_from(value: Time) -> Result<Self, Self::Error> {
+            time::Time::from_hms_nano(value.hour, value.minute, value.second, value.nanosecond)
+                .map_err(|_| invalid(value.into(), "a time"))
+        }
+    }
+
+    impl TryFrom<Datetime> for time::Date {
+        type Error = ConfigError;
+
+        fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+            match datetime {
+                Datetime {
+                    date: Some(date),
+                    time: None,
+                    offset: None,
+                } => date.try_into(),
+                _ => Err(invalid(datetime, "a local date")),
+            }
+        }
+    }
+
+    impl TryFrom<Datetime> for time::Time {
+        type Error = ConfigError;
+
+        fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+            match datetime {
+                Datetime {
+                    date: None,
+                    time: Some(time),
+                    offset: None,
+                } => time.try_into(),
+                _ => Err(invalid(datetime, "a local time")),
+            }
+        }
+    }
+
+    impl TryFrom<Datetime> for PrimitiveDateTime {
+        type Error = ConfigError;
+
+        fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+            match datetime {
+                Datetime {
+                    date: Some(date),
+                    time: Some(time),
+                    offset: None,
+                } => Ok(PrimitiveDateTime::new(date.try_into()?, time.try_into()?)),
+                _ => Err(invalid(datetime, "a local datetime")),
+            }
+        }
+    }
+
+    impl TryFrom<Datetime> for OffsetDateTime {
+        type Error = ConfigError;
+
+        fn try_from(datetime: Datetime) -> Result<Self, Self::Error> {
+            let minutes = match datetime.offset {
+                Some(Offset::Z) => 0,
+                Some(Offset::Custom { minutes }) => minutes,
+                None => return Err(invalid(datetime, "an offset datetime")),
+            };
+
+            let local = PrimitiveDateTime::try_from(Datetime {
+                offset: None,
+                ..datetime
+            })?;
+
+            UtcOffset::from_whole_seconds(i32::from(minutes) * 60)
+                .map(|offset| local.assume_offset(offset))
+                .map_err(|_| invalid(datetime, "an offset datetime"))
+        }
+    }
+
+    fn invalid(datetime: Datetime, expected: &'static str) -> ConfigError {
+        ConfigError::invalid_type(None, Unexpected::Datetime(datetime), expected)
+    }
+}
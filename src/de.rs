@@ -7,6 +7,7 @@ use serde::de;
 use crate::config::Config;
 use crate::error::{ConfigError, Result, Unexpected};
 use crate::map::Map;
+use crate::units::{BYTE_SIZE_NAME, DURATION_FIELDS, DURATION_NAME};
 use crate::value::{Table, Value, ValueKind};
 
 macro_rules! try_convert_number {
@@ -50,7 +51,11 @@ impl<'de> de::Deserializer<'de> for Value {
             ValueKind::U128(i) => visitor.visit_u128(i),
             ValueKind::Boolean(b) => visitor.visit_bool(b),
             ValueKind::Float(f) => visitor.visit_f64(f),
+            // Only types asking for a string get the exact digits of a decimal
+            ValueKind::Decimal(_) => visitor.visit_f64(self.into_float()?),
             ValueKind::String(s) => visitor.visit_string(s),
+            ValueKind::Bytes(b) => visitor.visit_byte_buf(b),
+            ValueKind::Datetime(d) => visitor.visit_string(d.to_string()),
             ValueKind::Array(values) => visitor.visit_seq(SeqAccess::new(values)),
             ValueKind::Table(map) => visitor.visit_map(MapAccess::new(map)),
         }
@@ -85,6 +90,11 @@ impl<'de> de::Deserializer<'de> for Value {
         visitor.visit_i64(num)
     }
 
+    #[inline]
+    fn deserialize_i128<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_i128(self.into_int128()?)
+    }
+
     #[inline]
     fn deserialize_u8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         let num = try_convert_number!(unsigned, self, "8");
@@ -109,6 +119,11 @@ impl<'de> de::Deserializer<'de> for Value {
         visitor.visit_u64(num)
     }
 
+    #[inline]
+    fn deserialize_u128<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_u128(self.into_uint128()?)
+    }
+
     #[inline]
     fn deserialize_f32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         visitor.visit_f32(self.into_float()? as f32)
@@ -129,6 +144,36 @@ impl<'de> de::Deserializer<'de> for Value {
         visitor.visit_string(self.into_string()?)
     }
 
+    #[inline]
+    fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_byte_buf(self.into_bytes()?)
+    }
+
+    #[inline]
+    fn deserialize_byte_buf<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_byte_buf(self.into_bytes()?)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // A `Vec<u8>` asks for a sequence, so bytes are given one byte at a time. Encoded strings
+        // are only decoded for a byte buffer, which asks for bytes
+        if let ValueKind::Bytes(_) = self.kind {
+            let origin = self.origin().map(ToOwned::to_owned);
+            let bytes = self.into_bytes()?;
+            return visitor.visit_seq(SeqAccess::new(
+                bytes
+                    .into_iter()
+                    .map(|byte| Value::new(origin.as_ref(), byte))
+                    .collect(),
+            ));
+        }
+
+        self.deserialize_any(visitor)
+    }
+
     #[inline]
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
     where
@@ -141,13 +186,65 @@ impl<'de> de::Deserializer<'de> for Value {
         }
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        if name == BYTE_SIZE_NAME {
+            let origin = self.origin().map(ToOwned::to_owned);
+            let bytes = self.into_byte_size()?;
+            return visitor.visit_newtype_struct(Value::new(origin.as_ref(), bytes));
+        }
+
         visitor.visit_newtype_struct(self)
     }
 
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // `std::time::Duration` may also be given as a number of seconds or a string like "1h30m"
+        if name == DURATION_NAME
+            && fields == DURATION_FIELDS
+            && !matches!(self.kind, ValueKind::Table(_) | ValueKind::Array(_))
+        {
+            let duration = self.into_duration()?;
+            return visitor.visit_seq(SeqAccess::new(vec![
+                duration.as_secs().into(),
+                duration.subsec_nanos().into(),
+            ]));
+        }
+
+        self.deserialize_any(visitor)
+    }
+
+    #[cfg_attr(not(feature = "time"), allow(unused_variables))]
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // The types of `time` ask for a tuple of their fields, unless human-readable
+        #[cfg(feature = "time")]
+        if let ValueKind::Datetime(datetime) = self.kind {
+            if let Some(fields) = crate::datetime::time_fields(datetime, len) {
+                let origin = self.origin().map(ToOwned::to_owned);
+                return visitor.visit_seq(SeqAccess::new(
+                    fields?
+                        .into_iter()
+                        .map(|field| Value::new(origin.as_ref(), field))
+                        .collect(),
+                ));
+            }
+        }
+
+        self.deserialize_any(visitor)
+    }
+
     fn deserialize_enum<V>(
         self,
         name: &'static str,
@@ -165,9 +262,8 @@ impl<'de> de::Deserializer<'de> for Value {
     }
 
     serde::forward_to_deserialize_any! {
-        char seq
-        bytes byte_buf map struct unit
-        identifier ignored_any unit_struct tuple_struct tuple
+        char map unit
+        identifier ignored_any unit_struct tuple_struct
     }
 }
 
@@ -269,7 +365,7 @@ struct EnumAccess {
 }
 
 impl EnumAccess {
-    fn variant_deserializer(&self, name: &str) -> Result<StrDeserializer> {
+    fn variant_deserializer(&self, name: &str) -> Result<StrDeserializer<'_>> {
         self.variants
             .iter()
             .find(|&&s| s.to_lowercase() == name.to_lowercase()) // changing to lowercase will enable deserialization of lowercase values to enums
@@ -277,7 +373,7 @@ impl EnumAccess {
             .ok_or_else(|| self.no_constructor_error(name))
     }
 
-    fn table_deserializer(&self, table: &Table) -> Result<StrDeserializer> {
+    fn table_deserializer(&self, table: &Table) -> Result<StrDeserializer<'_>> {
         if table.len() == 1 {
             self.variant_deserializer(table.iter().next().unwrap().0)
         } else {
@@ -389,10 +485,12 @@ impl<'de> de::Deserializer<'de> for Config {
         deserialize_i16;
         deserialize_i32;
         deserialize_i64;
+        deserialize_i128;
         deserialize_u8;
         deserialize_u16;
         deserialize_u32;
         deserialize_u64;
+        deserialize_u128;
         deserialize_f32;
         deserialize_f64;
         deserialize_str;
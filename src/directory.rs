@@ -0,0 +1,148 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{ConfigError, Result};
+use crate::map::Map;
+use crate::source::Source;
+use crate::value::{Value, ValueKind};
+
+/// A configuration source reading a directory tree with one file per key, such as a Kubernetes
+/// ConfigMap or Secret mounted as a volume, or the `$CREDENTIALS_DIRECTORY` of a systemd unit.
+///
+/// The path of each file relative to the directory is the key it sets, with subdirectories as
+/// tables, and its content is the value, without trailing newlines. Content that is not valid
+/// UTF-8 is read as [bytes](crate::Config::get_bytes).
+///
+/// Files and directories whose name starts with `.` are skipped, among which the `..data`
+/// symlink and the timestamped directories Kubernetes uses to update a volume atomically. The
+/// symlinks pointing into them are followed.
+///
+/// ```rust,no_run
+/// # use config::{Config, Directory};
+/// let config = Config::builder()
+///     .add_source(Directory::new("/etc/myapp").try_parsing(true))
+///     .add_source(Directory::new("/run/secrets/myapp").required(false))
+///     .build()?;
+///
+/// // From the file `/etc/myapp/database/port`
+/// let port = config.get_int("database.port")?;
+/// # Ok::<(), config::ConfigError>(())
+/// ```
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Directory {
+    path: PathBuf,
+
+    /// A required directory will error if it cannot be found
+    required: bool,
+
+    /// Parses booleans, integers and floats if they're detected (can be safely parsed).
+    try_parsing: bool,
+}
+
+impl Directory {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            required: true,
+            try_parsing: false,
+        }
+    }
+
+    /// Set required to false to make a directory optional when building the config.
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Parses booleans, integers and floats, like [`Environment::try_parsing`].
+    ///
+    /// [`Environment::try_parsing`]: crate::Environment::try_parsing
+    pub fn try_parsing(mut self, try_parsing: bool) -> Self {
+        self.try_parsing = try_parsing;
+        self
+    }
+
+    fn collect_dir(&self, dir: &Path, prefix: &str, map: &mut Map<String, Value>) -> Result<()> {
+        let mut entries = fs::read_dir(dir)
+            .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+            .map_err(|err| read_error(dir, err))?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+
+            let key = format!("{}{}", prefix, name);
+            let path = entry.path();
+            // Follows symlinks, which is how a mounted volume exposes its keys
+            let metadata = fs::metadata(&path).map_err(|err| read_error(&path, err))?;
+
+            if metadata.is_dir() {
+                self.collect_dir(&path, &format!("{}.", key), map)?;
+            } else {
+                let content = fs::read(&path).map_err(|err| read_error(&path, err))?;
+                let uri = path.to_string_lossy().into_owned();
+                map.insert(key, Value::new(Some(&uri), self.parse_value(content)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_value(&self, content: Vec<u8>) -> ValueKind {
+        let mut text = match String::from_utf8(content) {
+            Ok(text) => text,
+            Err(err) => return ValueKind::Bytes(err.into_bytes()),
+        };
+
+        let len = text.trim_end_matches(&['\n', '\r'][..]).len();
+        text.truncate(len);
+
+        let parsed = if self.try_parsing {
+            ValueKind::infer(&text)
+        } else {
+            None
+        };
+
+        parsed.unwrap_or(ValueKind::String(text))
+    }
+}
+
+impl Source for Directory {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        let mut map = Map::new();
+
+        if !self.path.is_dir() {
+            if !self.required {
+                return Ok(map);
+            }
+
+            return Err(ConfigError::Foreign(Box::new(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "configuration directory \"{}\" not found",
+                    self.path.to_string_lossy()
+                ),
+            ))));
+        }
+
+        self.collect_dir(&self.path, "", &mut map)?;
+        Ok(map)
+    }
+}
+
+fn read_error(path: &Path, err: io::Error) -> ConfigError {
+    ConfigError::FileParse {
+        uri: Some(path.to_string_lossy().into_owned()),
+        cause: Box::new(err),
+    }
+}
@@ -0,0 +1,209 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{ConfigError, Result};
+use crate::file::FileFormat;
+use crate::map::Map;
+use crate::source::Source;
+use crate::value::{Value, ValueKind};
+
+/// A configuration source embedded in the executable, parsed when it was built.
+///
+/// It is created by [`include_config!`](crate::include_config) from a file prepared by
+/// [`embed`] in the build script, and gives back the values of the file without parsing it
+/// again.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Embedded {
+    values: Map<String, Value>,
+}
+
+impl Embedded {
+    #[doc(hidden)]
+    pub fn from_values(values: Map<String, Value>) -> Self {
+        Self { values }
+    }
+}
+
+impl Source for Embedded {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        Ok(self.values.clone())
+    }
+}
+
+/// Parses a configuration file from a build script, and writes the code rebuilding its values
+/// to `$OUT_DIR` for [`include_config!`](crate::include_config) to embed.
+///
+/// A file that cannot be parsed with `format` is an error, failing the build. The build script
+/// is run again whenever the file changes.
+///
+/// The path is relative to the package and names the file to `include_config!` as well, so it
+/// cannot be absolute nor go through `..`.
+///
+/// ```rust,no_run
+/// // build.rs, with `config` in `[build-dependencies]`
+/// fn main() -> Result<(), config::ConfigError> {
+///     config::embed("config/default.toml", config::FileFormat::Toml)
+/// }
+/// ```
+///
+/// ```rust,ignore
+/// // src/main.rs
+/// let settings = config::Config::builder()
+///     .add_source(config::include_config!("config/default.toml"))
+///     .add_source(config::Environment::with_prefix("APP"))
+///     .build()?;
+/// ```
+///
+/// The code refers to this crate as `config`, which is the name it must be available by.
+pub fn embed<P: AsRef<Path>>(path: P, format: FileFormat) -> Result<()> {
+    let path = path.as_ref();
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| {
+        ConfigError::Message("OUT_DIR is not set, `embed` must run in a build script".into())
+    })?;
+
+    // The code is written under the whole path, so files of the same name in different
+    // directories do not overwrite each other
+    let relative = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !relative || path.file_name().is_none() {
+        return Err(ConfigError::Message(format!(
+            "configuration file \"{}\" must be a relative path without `..`",
+            path.display()
+        )));
+    }
+
+    let mut out = PathBuf::from(out_dir).join(path).into_os_string();
+    out.push(".rs");
+    let out = PathBuf::from(out);
+
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let uri = path.to_string_lossy().into_owned();
+    let text = fs::read_to_string(path).map_err(|err| ConfigError::FileParse {
+        uri: Some(uri.clone()),
+        cause: Box::new(err),
+    })?;
+    let values = format
+        .parse(Some(&uri), &text)
+        .map_err(|cause| ConfigError::FileParse {
+            uri: Some(uri.clone()),
+            cause,
+        })?;
+
+    if let Some(dir) = out.parent() {
+        fs::create_dir_all(dir).map_err(|err| ConfigError::Foreign(Box::new(err)))?;
+    }
+    fs::write(&out, generate(&uri, &values)).map_err(|err| ConfigError::Foreign(Box::new(err)))
+}
+
+/// Embeds the configuration file at the given path written by [`embed`] in the build script,
+/// giving an [`Embedded`](crate::Embedded) source.
+#[macro_export]
+macro_rules! include_config {
+    ($name:expr) => {
+        include!(concat!(env!("OUT_DIR"), "/", $name, ".rs"))
+    };
+}
+
+/// Returns the code of an expression building an [`Embedded`] source with `values`.
+fn generate(uri: &str, values: &Map<String, Value>) -> String {
+    let mut code = String::new();
+    code.push_str("// Generated by config::embed, do not edit\n");
+    code.push_str("{\n");
+    let _ = writeln!(
+        code,
+        "    let origin = ::std::string::String::from({:?});",
+        uri
+    );
+    code.push_str("    let origin = ::std::option::Option::Some(&origin);\n");
+    code.push_str("    ::config::Embedded::from_values(");
+    write_table(&mut code, values, 1);
+    code.push_str(")\n}\n");
+    code
+}
+
+fn write_table(code: &mut String, table: &Map<String, Value>, indent: usize) {
+    let pad = "    ".repeat(indent);
+
+    // Sorted so that the code does not change from one build to the next
+    let mut entries: Vec<_> = table.iter().collect();
+    entries.sort_by_key(|(key, _)| *key);
+
+    code.push_str("{\n");
+    let _ = writeln!(code, "{}    let mut table = ::config::Map::new();", pad);
+    for (key, value) in entries {
+        let _ = write!(
+            code,
+            "{}    table.insert(::std::string::String::from({:?}), ",
+            pad, key
+        );
+        write_value(code, value, indent + 1);
+        code.push_str(");\n");
+    }
+    let _ = write!(code, "{}    table\n{}}}", pad, pad);
+}
+
+fn write_value(code: &mut String, value: &Value, indent: usize) {
+    code.push_str("::config::Value::new(origin, ::config::ValueKind::");
+
+    let _ = match &value.kind {
+        ValueKind::Nil => write!(code, "Nil"),
+        ValueKind::Boolean(value) => write!(code, "Boolean({})", value),
+        ValueKind::I64(value) => write!(code, "I64({}i64)", value),
+        ValueKind::I128(value) => write!(code, "I128({}i128)", value),
+        ValueKind::U64(value) => write!(code, "U64({}u64)", value),
+        ValueKind::U128(value) => write!(code, "U128({}u128)", value),
+        ValueKind::Float(value) => write!(code, "Float({})", float_literal(*value)),
+        ValueKind::Decimal(value) => {
+            write!(code, "Decimal(::std::string::String::from({:?}))", value)
+        }
+        ValueKind::String(value) => {
+            write!(code, "String(::std::string::String::from({:?}))", value)
+        }
+        ValueKind::Bytes(value) => write!(code, "Bytes(::std::vec!{:?})", value),
+        ValueKind::Datetime(value) => write!(
+            code,
+            "Datetime({:?}.parse::<::config::Datetime>().unwrap())",
+            value.to_string()
+        ),
+        ValueKind::Table(table) => {
+            code.push_str("Table(");
+            write_table(code, table, indent);
+            write!(code, ")")
+        }
+        ValueKind::Array(values) => {
+            let pad = "    ".repeat(indent);
+            code.push_str("Array(::std::vec![\n");
+            for value in values {
+                code.push_str(&pad);
+                code.push_str("    ");
+                write_value(code, value, indent + 1);
+                code.push_str(",\n");
+            }
+            write!(code, "{}])", pad)
+        }
+    };
+
+    code.push(')');
+}
+
+fn float_literal(value: f64) -> String {
+    if value.is_nan() {
+        "::std::f64::NAN".into()
+    } else if value.is_infinite() && value > 0.0 {
+        "::std::f64::INFINITY".into()
+    } else if value.is_infinite() {
+        "::std::f64::NEG_INFINITY".into()
+    } else {
+        // Debug formatting gives back the exact value when parsed
+        format!("{:?}f64", value)
+    }
+}
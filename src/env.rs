@@ -1,6 +1,14 @@
+use std::collections::HashSet;
 use std::env;
-
-use crate::error::Result;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::sync::Arc;
+
+use crate::error::{ConfigError, Result, Unexpected};
+#[cfg(feature = "json")]
+use crate::file::format::json::from_json_value;
+use crate::format::Format;
 use crate::map::Map;
 use crate::source::Source;
 use crate::value::{Value, ValueKind};
@@ -44,12 +52,33 @@ pub struct Environment {
     /// A list of keys which should always be parsed as a list. If not set you can have only `Vec<String>` or `String` (not both) in one environment.
     list_parse_keys: Option<Vec<String>>,
 
+    /// Types of the keys matching a pattern, which take precedence over `try_parsing`.
+    type_hints: Vec<(String, TypeHint)>,
+
+    /// Formats parsing the values of the keys matching a pattern.
+    value_formats: Vec<(String, Shared<dyn Format + Send + Sync>)>,
+
+    /// Format parsing the values of all other keys.
+    value_format: Option<Shared<dyn Format + Send + Sync>>,
+
+    /// Maps the key of each variable to the path it sets, or `None` to skip it.
+    key_mapper: Option<Shared<KeyMapper>>,
+
+    /// Transforms each value once parsed, knowing its key.
+    value_transformer: Option<Shared<ValueTransformer>>,
+
+    /// Suffix of the variables whose value is the path of a file holding the actual value.
+    file_suffix: Option<String>,
+
     /// Ignore empty env values (treat as unset).
     ignore_empty: bool,
 
     /// Parses booleans, integers and floats if they're detected (can be safely parsed).
     try_parsing: bool,
 
+    /// Maps numeric key segments to array indices.
+    array_indices: bool,
+
     // Preserve the prefix while parsing
     keep_prefix: bool,
 
@@ -136,6 +165,9 @@ impl Environment {
     /// Optional character sequence that separates each key segment in an environment key pattern.
     /// Consider a nested configuration such as `redis.password`, a separator of `_` would allow
     /// an environment key of `REDIS_PASSWORD` to match.
+    ///
+    /// Numeric segments after the first are array indices, so with a separator of `__` the keys
+    /// `SERVERS__0__HOST` and `SERVERS__1__HOST` configure a list of two servers.
     pub fn separator(mut self, s: &str) -> Self {
         self.separator = Some(s.into());
         self
@@ -153,6 +185,8 @@ impl Environment {
     /// Add a key which should be parsed as a list when collecting [`Value`]s from the environment.
     /// Once list_separator is set, the type for string is [`Vec<String>`].
     /// To switch the default type back to type Strings you need to provide the keys which should be [`Vec<String>`] using this function.
+    ///
+    /// See [`with_type_hint`](Self::with_type_hint) for lists that do not depend on `try_parsing`.
     pub fn with_list_parse_key(mut self, key: &str) -> Self {
         if self.list_parse_keys.is_none() {
             self.list_parse_keys = Some(vec![key.to_lowercase()])
@@ -165,6 +199,170 @@ impl Environment {
         self
     }
 
+    /// Reads the variables whose key matches `pattern` as the type given by `hint`, whether
+    /// [`try_parsing`](Self::try_parsing) is set or not.
+    ///
+    /// The pattern is a key as it appears in the configuration, such as `database.port`, in which
+    /// `*` matches any sequence of characters, as in `servers.*.port`. When several patterns
+    /// match a key, the one added first is used.
+    ///
+    /// This generalises [`with_list_parse_key`](Self::with_list_parse_key): a key hinted as a
+    /// [`TypeHint::List`] is split on its own separator.
+    ///
+    /// ```rust
+    /// # use config::{Config, Environment, TypeHint};
+    /// # use std::collections::HashMap;
+    /// let mut vars = HashMap::new();
+    /// vars.insert("APP_ZIP_CODE".to_owned(), "01234".to_owned());
+    /// vars.insert("APP_PORT".to_owned(), "8080".to_owned());
+    ///
+    /// let config = Config::builder()
+    ///     .add_source(
+    ///         Environment::with_prefix("APP")
+    ///             .try_parsing(true)
+    ///             .with_type_hint("zip_code", TypeHint::String)
+    ///             .source(Some(vars.into_iter().collect())),
+    ///     )
+    ///     .build()?;
+    ///
+    /// assert_eq!(config.get_string("zip_code")?, "01234");
+    /// assert_eq!(config.get_int("port")?, 8080);
+    /// # Ok::<(), config::ConfigError>(())
+    /// ```
+    pub fn with_type_hint(mut self, pattern: &str, hint: TypeHint) -> Self {
+        self.type_hints.push((pattern.to_lowercase(), hint));
+        self
+    }
+
+    /// Parses every value with `format`, so that structured values such as
+    /// `{"a": true, "b": [1, 2]}` are merged as nested tables and arrays.
+    ///
+    /// A value `format` fails to parse is an error naming the variable. Keys with a
+    /// [type hint](Self::with_type_hint) or a [value format](Self::with_value_format) of their
+    /// own are not affected.
+    pub fn value_format<F>(mut self, format: F) -> Self
+    where
+        F: Format + Send + Sync + 'static,
+    {
+        self.value_format = Some(Shared(Arc::new(format)));
+        self
+    }
+
+    /// Parses the values of the keys matching `pattern` with `format`, as done for all keys by
+    /// [`value_format`](Self::value_format).
+    ///
+    /// Patterns are matched as in [`with_type_hint`](Self::with_type_hint), whose hints take
+    /// precedence.
+    ///
+    /// ```rust
+    /// # use config::{Config, Environment, FileFormat};
+    /// # use std::collections::HashMap;
+    /// let mut vars = HashMap::new();
+    /// vars.insert(
+    ///     "APP_FEATURE_FLAGS".to_owned(),
+    ///     r#"{"a": true, "b": [1, 2]}"#.to_owned(),
+    /// );
+    ///
+    /// let config = Config::builder()
+    ///     .add_source(
+    ///         Environment::with_prefix("APP")
+    ///             .with_value_format("feature_flags", FileFormat::Json)
+    ///             .source(Some(vars.into_iter().collect())),
+    ///     )
+    ///     .build()?;
+    ///
+    /// assert!(config.get_bool("feature_flags.a")?);
+    /// assert_eq!(config.get::<Vec<i32>>("feature_flags.b")?, [1, 2]);
+    /// # Ok::<(), config::ConfigError>(())
+    /// ```
+    pub fn with_value_format<F>(mut self, pattern: &str, format: F) -> Self
+    where
+        F: Format + Send + Sync + 'static,
+    {
+        self.value_formats
+            .push((pattern.to_lowercase(), Shared(Arc::new(format))));
+        self
+    }
+
+    /// Maps the key of each variable to the configuration path it sets, returning `None` to skip
+    /// the variable.
+    ///
+    /// The mapper receives the key as it would otherwise be used, that is lowercased, without
+    /// the prefix, with separators replaced by `.` and converted to the chosen case. The path it
+    /// returns is what [type hints](Self::with_type_hint) and
+    /// [value formats](Self::with_value_format) are matched against.
+    ///
+    /// ```rust
+    /// # use config::{Config, Environment};
+    /// # use std::collections::HashMap;
+    /// let mut vars = HashMap::new();
+    /// vars.insert("MYAPP_DB_HOST".to_owned(), "db.local".to_owned());
+    /// vars.insert("MYAPP_INTERNAL".to_owned(), "ignored".to_owned());
+    ///
+    /// let config = Config::builder()
+    ///     .add_source(
+    ///         Environment::with_prefix("MYAPP")
+    ///             .key_mapper(|key| match key.strip_prefix("db_") {
+    ///                 Some(rest) => Some(format!("database.{}", rest)),
+    ///                 None if key == "internal" => None,
+    ///                 None => Some(key.to_owned()),
+    ///             })
+    ///             .source(Some(vars.into_iter().collect())),
+    ///     )
+    ///     .build()?;
+    ///
+    /// assert_eq!(config.get_string("database.host")?, "db.local");
+    /// assert!(config.get_string("internal").is_err());
+    /// # Ok::<(), config::ConfigError>(())
+    /// ```
+    pub fn key_mapper<F>(mut self, mapper: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.key_mapper = Some(Shared(Arc::new(mapper)));
+        self
+    }
+
+    /// Transforms each value once parsed, for example to decrypt it, given the path it sets.
+    ///
+    /// An error returned by the transformer fails the collection, naming the variable.
+    pub fn value_transformer<F>(mut self, transformer: F) -> Self
+    where
+        F: Fn(&str, Value) -> std::result::Result<Value, Box<dyn Error + Send + Sync>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.value_transformer = Some(Shared(Arc::new(transformer)));
+        self
+    }
+
+    /// Reads the value of a variable whose name ends with `suffix` from the file it names, as in
+    /// the `DB_PASSWORD_FILE=/run/secrets/db_password` convention of Docker and Kubernetes secrets.
+    ///
+    /// The variable sets the key it would without the suffix, taking precedence over a variable
+    /// of that name. Trailing newlines of the file are trimmed, and a file that cannot be read is
+    /// an error naming the variable. The suffix is matched regardless of case.
+    ///
+    /// Only variables with the [prefix](Self::prefix), and not dropped by the
+    /// [key mapper](Self::key_mapper), are read, so without either any variable of the process
+    /// ending with the suffix must name a readable file.
+    ///
+    /// ```rust,no_run
+    /// # use config::{Config, Environment};
+    /// // With `APP_DB_PASSWORD_FILE=/run/secrets/db_password`
+    /// let config = Config::builder()
+    ///     .add_source(Environment::with_prefix("APP").separator("_").file_suffix("_FILE"))
+    ///     .build()?;
+    ///
+    /// let password = config.get_string("db.password")?;
+    /// # Ok::<(), config::ConfigError>(())
+    /// ```
+    pub fn file_suffix(mut self, suffix: &str) -> Self {
+        self.file_suffix = Some(suffix.into());
+        self
+    }
+
     /// Ignore empty env values (treat as unset).
     pub fn ignore_empty(mut self, ignore: bool) -> Self {
         self.ignore_empty = ignore;
@@ -178,6 +376,16 @@ impl Environment {
         self
     }
 
+    /// Maps numeric key segments to array indices, so with a separator of `__` the variable
+    /// `SERVERS__0__HOST` sets `servers[0].host` rather than the key `0` of a `servers` table.
+    ///
+    /// An index may be past the end of the array, which is then padded with nil values, but never
+    /// above 1024.
+    pub fn array_indices(mut self, enable: bool) -> Self {
+        self.array_indices = enable;
+        self
+    }
+
     // Preserve the prefix while parsing
     pub fn keep_prefix(mut self, keep: bool) -> Self {
         self.keep_prefix = keep;
@@ -248,13 +456,20 @@ impl Source for Environment {
             .as_ref()
             .map(|prefix| format!("{}{}", prefix, prefix_separator).to_lowercase());
 
-        let collector = |(key, value): (String, String)| {
+        // Keys set from a file, which a variable naming the key directly does not replace
+        let mut file_keys = HashSet::new();
+
+        let mut collector = |(name, value): (String, String)| -> Result<()> {
             // Treat empty environment variables as unset
             if self.ignore_empty && value.is_empty() {
-                return;
+                return Ok(());
             }
 
-            let mut key = key.to_lowercase();
+            let file_name = self
+                .file_suffix
+                .as_deref()
+                .and_then(|suffix| strip_suffix_ignore_case(&name, suffix));
+            let mut key = file_name.unwrap_or(&name).to_lowercase();
 
             // Check for prefix
             if let Some(ref prefix_pattern) = prefix_pattern {
@@ -265,7 +480,7 @@ impl Source for Environment {
                     }
                 } else {
                     // Skip this key
-                    return;
+                    return Ok(());
                 }
             }
 
@@ -279,14 +494,46 @@ impl Source for Environment {
                 key = key.to_case(*convert_case);
             }
 
-            let value = if self.try_parsing {
-                // convert to lowercase because bool parsing expects all lowercase
-                if let Ok(parsed) = value.to_lowercase().parse::<bool>() {
-                    ValueKind::Boolean(parsed)
-                } else if let Ok(parsed) = value.parse::<i64>() {
-                    ValueKind::I64(parsed)
-                } else if let Ok(parsed) = value.parse::<f64>() {
-                    ValueKind::Float(parsed)
+            if let Some(ref mapper) = self.key_mapper {
+                match (mapper.0)(&key) {
+                    Some(mapped) => key = mapped,
+                    None => return Ok(()),
+                }
+            }
+
+            // Only read the files of the variables which are kept
+            let value = match file_name {
+                Some(_) => read_file_value(&name, &value)?,
+                None => value,
+            };
+
+            let lowercase_key = key.to_lowercase();
+            let hint = self
+                .type_hints
+                .iter()
+                .find(|(pattern, _)| glob_match(pattern, &lowercase_key));
+            let format = self
+                .value_formats
+                .iter()
+                .find(|(pattern, _)| glob_match(pattern, &lowercase_key))
+                .map(|(_, format)| format)
+                .or(self.value_format.as_ref());
+
+            let value = if let Some((_, hint)) = hint {
+                hint.parse(&uri, value)
+                    .map_err(|err| err.extend_with_key(&key))?
+            } else if let Some(format) = format {
+                format
+                    .0
+                    .parse_value(Some(&name), &value)
+                    .map_err(|cause| ConfigError::FileParse {
+                        uri: Some(name.clone()),
+                        cause,
+                    })?
+                    .kind
+            } else if self.try_parsing {
+                if let Some(parsed) = ValueKind::infer(&value) {
+                    parsed
                 } else if let Some(separator) = &self.list_separator {
                     if let Some(keys) = &self.list_parse_keys {
                         #[cfg(feature = "convert-case")]
@@ -315,14 +562,197 @@ impl Source for Environment {
                 ValueKind::String(value)
             };
 
-            m.insert(key, Value::new(Some(&uri), value));
+            let mut value = Value::new(Some(&uri), value);
+            if let Some(ref transformer) = self.value_transformer {
+                value = (transformer.0)(&key, value).map_err(|cause| ConfigError::FileParse {
+                    uri: Some(name.clone()),
+                    cause,
+                })?;
+            }
+
+            // Numeric segments index into arrays, so `SERVERS__0__HOST` sets `servers[0].host`
+            if self.array_indices && !separator.is_empty() {
+                key = array_subscripts(&key).map_err(|cause| ConfigError::FileParse {
+                    uri: Some(name.clone()),
+                    cause: cause.into(),
+                })?;
+            }
+
+            if file_name.is_some() {
+                file_keys.insert(key.clone());
+            } else if file_keys.contains(&key) {
+                return Ok(());
+            }
+
+            m.insert(key, value);
+            Ok(())
         };
 
         match &self.source {
-            Some(source) => source.clone().into_iter().for_each(collector),
-            None => env::vars().for_each(collector),
+            Some(source) => source.clone().into_iter().try_for_each(&mut collector)?,
+            None => env::vars().try_for_each(&mut collector)?,
         }
 
         Ok(m)
     }
 }
+
+/// Strips `suffix` from `name` regardless of case, unless nothing would be left.
+fn strip_suffix_ignore_case<'a>(name: &'a str, suffix: &str) -> Option<&'a str> {
+    let split = name.len().checked_sub(suffix.len())?;
+    let (head, tail) = (name.get(..split)?, name.get(split..)?);
+
+    if !head.is_empty() && !suffix.is_empty() && tail.eq_ignore_ascii_case(suffix) {
+        Some(head)
+    } else {
+        None
+    }
+}
+
+/// Reads the value of the variable `name` from the file at `path`, without trailing newlines.
+fn read_file_value(name: &str, path: &str) -> Result<String> {
+    let mut contents = fs::read_to_string(path).map_err(|err| ConfigError::FileParse {
+        uri: Some(name.to_owned()),
+        cause: format!("could not read `{}`: {}", path, err).into(),
+    })?;
+
+    let len = contents.trim_end_matches(&['\n', '\r'][..]).len();
+    contents.truncate(len);
+    Ok(contents)
+}
+
+type KeyMapper = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+type ValueTransformer =
+    dyn Fn(&str, Value) -> std::result::Result<Value, Box<dyn Error + Send + Sync>> + Send + Sync;
+
+/// A format or callback shared by clones of the source, which need not implement `Debug`.
+struct Shared<T: ?Sized>(Arc<T>);
+
+impl<T: ?Sized> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Shared(..)")
+    }
+}
+
+/// The type of an environment variable, see [`Environment::with_type_hint`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeHint {
+    /// A string, even when it looks like a number or a boolean.
+    String,
+
+    /// An integer.
+    Int,
+
+    /// A floating point number.
+    Float,
+
+    /// A boolean, in any case.
+    Bool,
+
+    /// A list of strings, split on the given separator.
+    List(String),
+
+    /// A JSON value, such as `[1, 2]` or `{"enabled": true}`.
+    #[cfg(feature = "json")]
+    Json,
+}
+
+impl TypeHint {
+    fn parse(&self, uri: &String, value: String) -> Result<ValueKind> {
+        let (kind, expected) = match *self {
+            Self::String => (Some(ValueKind::String(value.clone())), "a string"),
+            Self::Int => (
+                ValueKind::parse_number(&value).filter(|kind| {
+                    matches!(
+                        kind,
+                        ValueKind::I64(_)
+                            | ValueKind::I128(_)
+                            | ValueKind::U64(_)
+                            | ValueKind::U128(_)
+                    )
+                }),
+                "an integer",
+            ),
+            Self::Float => (value.parse().ok().map(ValueKind::Float), "a floating point"),
+            Self::Bool => (
+                value.to_lowercase().parse().ok().map(ValueKind::Boolean),
+                "a boolean",
+            ),
+            Self::List(ref separator) => (
+                Some(ValueKind::Array(
+                    value
+                        .split(separator.as_str())
+                        .map(|s| Value::new(Some(uri), ValueKind::String(s.to_owned())))
+                        .collect(),
+                )),
+                "a list",
+            ),
+            #[cfg(feature = "json")]
+            Self::Json => (
+                serde_json::from_str(&value)
+                    .ok()
+                    .map(|json| from_json_value(Some(uri), &json).kind),
+                "a JSON value",
+            ),
+        };
+
+        kind.ok_or_else(|| {
+            ConfigError::invalid_type(Some(uri.clone()), Unexpected::Str(value), expected)
+        })
+    }
+}
+
+/// Matches a key against a pattern in which `*` stands for any sequence of characters.
+fn glob_match(pattern: &str, key: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == key,
+        Some(star) => {
+            let (prefix, rest) = (&pattern[..star], &pattern[star + 1..]);
+            match key.strip_prefix(prefix) {
+                Some(key) => (0..=key.len())
+                    .filter(|&i| key.is_char_boundary(i))
+                    .any(|i| glob_match(rest, &key[i..])),
+                None => false,
+            }
+        }
+    }
+}
+
+/// Largest array index a variable may set.
+const MAX_ARRAY_INDEX: usize = 1024;
+
+/// Turns the numeric segments of a dotted key into subscripts, e.g. `servers.0.host` into
+/// `servers[0].host`. The first segment always names a key of the root table.
+fn array_subscripts(key: &str) -> std::result::Result<String, String> {
+    let mut segments = key.split('.');
+    let mut path = segments.next().unwrap_or_default().to_owned();
+
+    for segment in segments {
+        if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+            match segment.parse::<usize>() {
+                Ok(index) if index <= MAX_ARRAY_INDEX => {}
+                _ => {
+                    return Err(format!(
+                        "array index {} is larger than {}",
+                        segment, MAX_ARRAY_INDEX
+                    ))
+                }
+            }
+            path.push('[');
+            path.push_str(segment);
+            path.push(']');
+        } else {
+            path.push('.');
+            path.push_str(segment);
+        }
+    }
+
+    Ok(path)
+}
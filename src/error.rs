@@ -5,6 +5,8 @@ use std::result;
 use serde::de;
 use serde::ser;
 
+use crate::datetime::Datetime;
+
 #[derive(Debug)]
 pub enum Unexpected {
     Bool(bool),
@@ -13,7 +15,10 @@ pub enum Unexpected {
     U64(u64),
     U128(u128),
     Float(f64),
+    Decimal(String),
     Str(String),
+    Bytes(Vec<u8>),
+    Datetime(Datetime),
     Unit,
     Seq,
     Map,
@@ -28,7 +33,10 @@ impl fmt::Display for Unexpected {
             Unexpected::U64(i) => write!(f, "64-bit unsigned integer `{}`", i),
             Unexpected::U128(i) => write!(f, "128-bit unsigned integer `{}`", i),
             Unexpected::Float(v) => write!(f, "floating point `{}`", v),
+            Unexpected::Decimal(ref v) => write!(f, "decimal `{}`", v),
             Unexpected::Str(ref s) => write!(f, "string {:?}", s),
+            Unexpected::Bytes(ref b) => write!(f, "byte array of length {}", b.len()),
+            Unexpected::Datetime(ref d) => write!(f, "datetime `{}`", d),
             Unexpected::Unit => write!(f, "unit value"),
             Unexpected::Seq => write!(f, "sequence"),
             Unexpected::Map => write!(f, "map"),
@@ -0,0 +1,129 @@
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A compression a configuration file can be stored with, told by its extension or magic bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    const ALL: [Self; 2] = [Self::Gzip, Self::Zstd];
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Zstd => "zst",
+        }
+    }
+
+    fn magic(self) -> &'static [u8] {
+        match self {
+            Self::Gzip => &[0x1f, 0x8b],
+            Self::Zstd => &[0x28, 0xb5, 0x2f, 0xfd],
+        }
+    }
+
+    /// The feature of the library decompressing files of this compression.
+    fn feature(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    fn is_enabled(self) -> bool {
+        match self {
+            Self::Gzip => cfg!(feature = "gzip"),
+            Self::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+
+    fn from_extension(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|compression| ext.eq_ignore_ascii_case(compression.extension()))
+    }
+
+    fn from_magic(bytes: &[u8]) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|compression| bytes.starts_with(compression.magic()))
+    }
+
+    #[cfg_attr(not(all(feature = "gzip", feature = "zstd")), allow(unused_variables))]
+    fn decode(self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => {
+                use std::io::Read;
+
+                let mut decoded = Vec::new();
+                flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut decoded)?;
+                Ok(decoded)
+            }
+
+            #[cfg(feature = "zstd")]
+            Self::Zstd => zstd::stream::decode_all(bytes),
+
+            #[allow(unreachable_patterns)]
+            _ => Err(io::Error::other(format!(
+                "it is compressed with {}, which needs the `{}` feature",
+                self.extension(),
+                self.feature()
+            ))),
+        }
+    }
+}
+
+/// Returns the paths of the compressed files `filename` can be stored as, with the extension of
+/// each compression enabled added.
+pub(crate) fn compressed_paths(filename: &Path) -> Vec<PathBuf> {
+    Compression::ALL
+        .iter()
+        .filter(|compression| compression.is_enabled())
+        .map(|compression| {
+            let mut path = OsString::from(filename);
+            path.push(".");
+            path.push(compression.extension());
+            PathBuf::from(path)
+        })
+        .collect()
+}
+
+/// Returns the extension telling the format of `filename`, the one before the extension of its
+/// compression if it has one, as `json` in `routes.json.gz`.
+pub(crate) fn format_extension(filename: &Path) -> Option<String> {
+    let filename = match Compression::from_extension(filename) {
+        Some(_) => filename.file_stem().map(Path::new)?,
+        None => filename,
+    };
+
+    filename
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+}
+
+/// Decompresses the content of the file labelled `uri` in errors if it starts with the magic
+/// bytes of a compression, and otherwise returns it unchanged.
+pub(crate) fn decompress(uri: &str, bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    let compression = match Compression::from_magic(&bytes) {
+        Some(compression) => compression,
+        None => return Ok(bytes),
+    };
+
+    compression.decode(&bytes).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "configuration file \"{}\" could not be decompressed: {}",
+                uri, err
+            ),
+        )
+    })
+}
@@ -1,37 +1,246 @@
 use std::error::Error;
+use std::fmt;
 
-use ini::Ini;
+use ini::{Ini, Properties};
 
+use crate::file::{FileFormat, FileStoredFormat};
+use crate::format::Format;
 use crate::map::Map;
-use crate::value::{Value, ValueKind};
+use crate::value::{Table, Value, ValueKind};
 
-pub fn parse(
-    uri: Option<&String>,
-    text: &str,
-) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
-    let mut map: Map<String, Value> = Map::new();
-    let i = Ini::load_from_str(text)?;
-    for (sec, prop) in i.iter() {
-        match sec {
-            Some(sec) => {
-                let mut sec_map: Map<String, Value> = Map::new();
-                for (k, v) in prop.iter() {
-                    sec_map.insert(
-                        k.to_owned(),
-                        Value::new(uri, ValueKind::String(v.to_owned())),
-                    );
-                }
-                map.insert(sec.to_owned(), Value::new(uri, ValueKind::Table(sec_map)));
+/// INI format with configurable handling of values and keys.
+///
+/// [`FileFormat::Ini`] behaves like `IniFormat::default()`, reading every value as a string and
+/// each section as a table under its full name.
+///
+/// ```rust
+/// # use config::{Config, File, IniFormat};
+/// let format = IniFormat::new()
+///     .try_parsing(true)
+///     .arrays(true)
+///     .nested_sections(true);
+///
+/// let config = Config::builder()
+///     .add_source(File::from_str(
+///         "
+/// [server.tls]
+/// enabled = true
+/// port = 8443
+/// hosts[] = example.com
+/// ",
+///         format,
+///     ))
+///     .build()?;
+///
+/// assert!(config.get_bool("server.tls.enabled")?);
+/// assert_eq!(config.get_int("server.tls.port")?, 8443);
+/// assert_eq!(config.get::<Vec<String>>("server.tls.hosts")?, ["example.com"]);
+/// # Ok::<(), config::ConfigError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+#[must_use]
+pub struct IniFormat {
+    try_parsing: bool,
+    arrays: bool,
+    nested_sections: bool,
+}
+
+impl IniFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses booleans, integers and floats, like [`Environment::try_parsing`].
+    ///
+    /// [`Environment::try_parsing`]: crate::Environment::try_parsing
+    pub fn try_parsing(mut self, try_parsing: bool) -> Self {
+        self.try_parsing = try_parsing;
+        self
+    }
+
+    /// Collects the values of a key repeated within a section into an array, as well as the
+    /// values of keys ending with `[]`, which are an array even when given once.
+    ///
+    /// Otherwise the last value of a repeated key wins.
+    pub fn arrays(mut self, arrays: bool) -> Self {
+        self.arrays = arrays;
+        self
+    }
+
+    /// Nests a dotted section name such as `[server.tls]` in the table of `server`, and adds a
+    /// section given more than once to the same table.
+    ///
+    /// A section and an option of the same name then conflict, which is an error. Otherwise a
+    /// section is a table under its full name, replacing any earlier value of that name.
+    pub fn nested_sections(mut self, nested_sections: bool) -> Self {
+        self.nested_sections = nested_sections;
+        self
+    }
+
+    fn parse_properties(
+        &self,
+        uri: Option<&String>,
+        section: Option<&str>,
+        properties: &Properties,
+        map: &mut Table,
+    ) -> Result<(), ConflictError> {
+        for (key, value) in properties.iter() {
+            let value = Value::new(uri, self.parse_value(value));
+
+            let (key, list) = match key.strip_suffix("[]") {
+                Some(key) if self.arrays => (key, true),
+                _ => (key, false),
+            };
+
+            // A section of the same name already made this key a table
+            if let Some(Value {
+                kind: ValueKind::Table(_),
+                ..
+            }) = map.get(key)
+            {
+                return Err(ConflictError::Option {
+                    section: section.map(str::to_owned),
+                    key: key.to_owned(),
+                });
             }
-            None => {
-                for (k, v) in prop.iter() {
+
+            if !self.arrays {
+                map.insert(key.to_owned(), value);
+                continue;
+            }
+
+            match map.get_mut(key) {
+                // INI values are never arrays themselves, so this one was collected here
+                Some(Value {
+                    kind: ValueKind::Array(values),
+                    ..
+                }) => values.push(value),
+                Some(existing) => {
+                    let first = std::mem::take(existing);
+                    *existing = Value::new(uri, ValueKind::Array(vec![first, value]));
+                }
+                None if list => {
                     map.insert(
-                        k.to_owned(),
-                        Value::new(uri, ValueKind::String(v.to_owned())),
+                        key.to_owned(),
+                        Value::new(uri, ValueKind::Array(vec![value])),
                     );
                 }
+                None => {
+                    map.insert(key.to_owned(), value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_value(&self, value: &str) -> ValueKind {
+        let parsed = if self.try_parsing {
+            ValueKind::infer(value)
+        } else {
+            None
+        };
+
+        parsed.unwrap_or_else(|| ValueKind::String(value.to_owned()))
+    }
+}
+
+impl Format for IniFormat {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+        let mut map: Map<String, Value> = Map::new();
+        let i = Ini::load_from_str(text)?;
+        for (sec, prop) in i.iter() {
+            match sec {
+                Some(sec) if self.nested_sections => {
+                    let sec_map = section_table(uri, &mut map, sec)?;
+                    self.parse_properties(uri, Some(sec), prop, sec_map)?;
+                }
+                Some(sec) => {
+                    let mut sec_map = Map::new();
+                    self.parse_properties(uri, Some(sec), prop, &mut sec_map)?;
+                    map.insert(sec.to_owned(), Value::new(uri, ValueKind::Table(sec_map)));
+                }
+                None => self.parse_properties(uri, None, prop, &mut map)?,
             }
         }
+        Ok(map)
+    }
+}
+
+impl FileStoredFormat for IniFormat {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        FileFormat::Ini.file_extensions()
+    }
+}
+
+pub fn parse(
+    uri: Option<&String>,
+    text: &str,
+) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+    IniFormat::default().parse(uri, text)
+}
+
+/// Returns the table of a possibly dotted section, creating the tables leading to it as needed.
+fn section_table<'a>(
+    uri: Option<&String>,
+    map: &'a mut Table,
+    section: &str,
+) -> Result<&'a mut Table, ConflictError> {
+    let mut table = map;
+    for segment in section.split('.') {
+        let value = table
+            .entry(segment.to_owned())
+            .or_insert_with(|| Value::new(uri, ValueKind::Table(Map::new())));
+
+        table = match value.kind {
+            ValueKind::Table(ref mut table) => table,
+            _ => return Err(ConflictError::Section(section.to_owned())),
+        };
+    }
+
+    Ok(table)
+}
+
+/// A section and an option given the same name.
+#[derive(Debug)]
+enum ConflictError {
+    Section(String),
+    Option {
+        section: Option<String>,
+        key: String,
+    },
+}
+
+impl fmt::Display for ConflictError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Section(ref section) => write!(
+                format,
+                "INI section `{}` conflicts with an option of the same name",
+                section
+            ),
+            Self::Option {
+                section: Some(ref section),
+                ref key,
+            } => write!(
+                format,
+                "INI option `{}` in section `{}` conflicts with a section of the same name",
+                key, section
+            ),
+            Self::Option {
+                section: None,
+                ref key,
+            } => write!(
+                format,
+                "INI option `{}` conflicts with a section of the same name",
+                key
+            ),
+        }
     }
-    Ok(map)
 }
+
+impl Error for ConflictError {}
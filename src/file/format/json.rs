@@ -13,19 +13,23 @@ pub fn parse(
     format::extract_root_table(uri, value)
 }
 
-fn from_json_value(uri: Option<&String>, value: &serde_json::Value) -> Value {
+pub fn parse_value(
+    uri: Option<&String>,
+    text: &str,
+) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    Ok(from_json_value(uri, &serde_json::from_str(text)?))
+}
+
+pub(crate) fn from_json_value(uri: Option<&String>, value: &serde_json::Value) -> Value {
     match *value {
         serde_json::Value::String(ref value) => Value::new(uri, ValueKind::String(value.clone())),
 
-        serde_json::Value::Number(ref value) => {
-            if let Some(value) = value.as_i64() {
-                Value::new(uri, ValueKind::I64(value))
-            } else if let Some(value) = value.as_f64() {
-                Value::new(uri, ValueKind::Float(value))
-            } else {
-                unreachable!();
-            }
-        }
+        // The text of a number keeps it exact, including all digits of decimals when
+        // serde_json is built with arbitrary precision
+        serde_json::Value::Number(ref value) => match ValueKind::parse_number(&value.to_string()) {
+            Some(kind) => Value::new(uri, kind),
+            None => unreachable!(),
+        },
 
         serde_json::Value::Bool(value) => Value::new(uri, ValueKind::Boolean(value)),
 
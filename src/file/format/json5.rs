@@ -1,54 +1,304 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::error::Error;
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
 
 use crate::format;
 use crate::map::Map;
 use crate::value::{Value, ValueKind};
 
-#[derive(serde::Deserialize, Debug)]
-#[serde(untagged)]
-pub enum Val {
-    Null,
-    Boolean(bool),
-    Integer(i64),
-    Float(f64),
-    String(String),
-    Array(Vec<Self>),
-    Object(Map<String, Self>),
-}
-
 pub fn parse(
     uri: Option<&String>,
     text: &str,
 ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
-    let value = from_json5_value(uri, json5_rs::from_str::<Val>(text)?);
+    // json5 only hands out numbers as `i64` or `f64`, and fails on integers that overflow an
+    // `i64`, so the text of every number is collected up front to be parsed exactly instead
+    let (text, numbers) = scan_numbers(text);
+    let numbers = RefCell::new(numbers);
+
+    let mut deserializer = json5_rs::Deserializer::from_str(&text)?;
+    let value = ValueSeed {
+        uri,
+        numbers: &numbers,
+    }
+    .deserialize(&mut deserializer)?;
+
+    if let Some(number) = numbers.into_inner().pop_front() {
+        return Err(format!(
+            "JSON5 number `{}` was found in the document but never read",
+            number.text
+        )
+        .into());
+    }
     format::extract_root_table(uri, value)
 }
 
-fn from_json5_value(uri: Option<&String>, value: Val) -> Value {
-    let vk = match value {
-        Val::Null => ValueKind::Nil,
-        Val::String(v) => ValueKind::String(v),
-        Val::Integer(v) => ValueKind::I64(v),
-        Val::Float(v) => ValueKind::Float(v),
-        Val::Boolean(v) => ValueKind::Boolean(v),
-        Val::Object(table) => {
-            let m = table
-                .into_iter()
-                .map(|(k, v)| (k, from_json5_value(uri, v)))
-                .collect();
+/// The text of a number in a JSON5 document.
+struct Number {
+    text: String,
+
+    /// Whether the number was rewritten as a float of the same length, for json5 to accept it
+    rewritten: bool,
+}
+
+impl Number {
+    /// A number json5 reads as it is.
+    fn read(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            rewritten: false,
+        }
+    }
+
+    /// Whether json5 reading this number would give `parsed`, which tells whether the numbers
+    /// are read in the order they were scanned in.
+    fn agrees_with(&self, parsed: &ValueKind) -> bool {
+        let digits = self.text.trim_start_matches(&['+', '-'][..]);
+        match *parsed {
+            _ if self.rewritten => *parsed == ValueKind::Float(0.0),
+            ValueKind::I64(value) => match hex_digits(digits) {
+                Some(hex) => i64::from_str_radix(hex, 16) == Ok(value),
+                None => self.text.parse::<i64>() == Ok(value),
+            },
+            ValueKind::Float(value) if value.is_nan() => digits == "NaN",
+            ValueKind::Float(value) if value.is_infinite() => {
+                digits == "Infinity" && self.text.starts_with('-') == (value < 0.0)
+            }
+            ValueKind::Float(value) => self.text.parse::<f64>() == Ok(value),
+            _ => false,
+        }
+    }
+}
+
+fn hex_digits(digits: &str) -> Option<&str> {
+    digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+}
+
+/// Deserializes a JSON5 value, taking the text of each number in document order from `numbers`.
+#[derive(Clone, Copy)]
+struct ValueSeed<'a> {
+    uri: Option<&'a String>,
+    numbers: &'a RefCell<VecDeque<Number>>,
+}
+
+impl<'a> ValueSeed<'a> {
+    fn number<E>(self, parsed: ValueKind) -> Result<Value, E>
+    where
+        E: de::Error,
+    {
+        let number = self
+            .numbers
+            .borrow_mut()
+            .pop_front()
+            .ok_or_else(|| E::custom("JSON5 number read beyond those found in the document"))?;
+        if !number.agrees_with(&parsed) {
+            return Err(E::custom(format!(
+                "JSON5 number `{}` was read out of document order",
+                number.text
+            )));
+        }
+
+        let digits = number.text.trim_start_matches(&['+', '-'][..]);
+        if hex_digits(digits).is_some() || matches!(digits, "Infinity" | "NaN") {
+            // Read exactly by json5 already
+            return Ok(Value::new(self.uri, parsed));
+        }
+
+        let integer = !number.text.contains(&['.', 'e', 'E'][..]);
+        match ValueKind::parse_number(&number.text) {
+            Some(ValueKind::Float(_)) if integer => Err(E::custom(format!(
+                "JSON5 integer `{}` does not fit in 128 bits",
+                number.text
+            ))),
+            Some(kind) => Ok(Value::new(self.uri, kind)),
+            None => Ok(Value::new(self.uri, parsed)),
+        }
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for ValueSeed<'a> {
+    type Value = Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for ValueSeed<'a> {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a JSON5 value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::new(self.uri, ValueKind::Nil))
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::new(self.uri, ValueKind::Boolean(value)))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E>
+    where
+        E: de::Error,
+    {
+        self.number(ValueKind::I64(value))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E>
+    where
+        E: de::Error,
+    {
+        self.number(ValueKind::Float(value))
+    }
 
-            ValueKind::Table(m)
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::new(self.uri, ValueKind::String(value)))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::new(self.uri, ValueKind::String(value.to_owned())))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut l = Vec::new();
+        while let Some(value) = seq.next_element_seed(self)? {
+            l.push(value);
         }
 
-        Val::Array(array) => {
-            let l = array
-                .into_iter()
-                .map(|v| from_json5_value(uri, v))
-                .collect();
+        Ok(Value::new(self.uri, ValueKind::Array(l)))
+    }
 
-            ValueKind::Array(l)
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut m = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let value = map.next_value_seed(self)?;
+            m.insert(key, value);
         }
-    };
 
-    Value::new(uri, vk)
+        Ok(Value::new(self.uri, ValueKind::Table(m)))
+    }
+}
+
+/// Collects the text of every number in a JSON5 document, in document order.
+///
+/// Decimal integers too large for an `i64` are rewritten as a float of the same length, so
+/// json5 accepts them without shifting the location of any error.
+fn scan_numbers(text: &str) -> (String, VecDeque<Number>) {
+    let bytes = text.as_bytes();
+    let mut rewritten = text.to_owned();
+    let mut numbers = VecDeque::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = text[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + end + 4);
+            }
+            c if is_identifier_byte(c) && !c.is_ascii_digit() => {
+                let start = i;
+                i = skip_identifier(bytes, i);
+                // `Infinity` and `NaN` are numbers, unless they name a key
+                if matches!(&text[start..i], "Infinity" | "NaN") && !is_key(text, i) {
+                    numbers.push_back(Number::read(&text[start..i]));
+                }
+            }
+            b'0'..=b'9' | b'.' | b'+' | b'-' => {
+                let start = i;
+                let sign = usize::from(matches!(bytes[i], b'+' | b'-'));
+                i += sign;
+                if bytes.get(i).is_some_and(u8::is_ascii_alphabetic) {
+                    // A signed `Infinity` or `NaN`
+                    i = skip_identifier(bytes, i);
+                    numbers.push_back(Number::read(&text[start..i]));
+                    continue;
+                }
+
+                let hex = matches!(bytes.get(i..i + 2), Some(b"0x" | b"0X"));
+                i = skip_number(bytes, i);
+                let number = &text[start..i];
+                let digits = start + sign..i;
+                let rewrite = !hex
+                    && digits.len() > 2
+                    && !number.contains(&['.', 'e', 'E'][..])
+                    && number.parse::<i64>().is_err();
+                if rewrite {
+                    let float = format!("0.{}", "0".repeat(digits.len() - 2));
+                    rewritten.replace_range(digits, &float);
+                }
+                numbers.push_back(Number {
+                    text: number.to_owned(),
+                    rewritten: rewrite,
+                });
+            }
+            _ => i += 1,
+        }
+    }
+
+    (rewritten, numbers)
+}
+
+fn is_identifier_byte(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$' | b'\\') || !c.is_ascii()
+}
+
+fn skip_identifier(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && is_identifier_byte(bytes[i]) {
+        i += 1;
+    }
+    i
+}
+
+fn skip_number(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'e' | b'E' if matches!(bytes.get(i + 1), Some(b'+' | b'-')) => i += 2,
+            c if c.is_ascii_alphanumeric() || c == b'.' => i += 1,
+            _ => break,
+        }
+    }
+    i
+}
+
+/// Whether the identifier ending at `end` is followed by a `:`, making it a key.
+fn is_key(text: &str, end: usize) -> bool {
+    let mut rest = &text[end..];
+    loop {
+        rest = rest.trim_start();
+        if let Some(comment) = rest.strip_prefix("//") {
+            rest = comment.find('\n').map_or("", |end| &comment[end..]);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.find("*/").map_or("", |end| &comment[end + 2..]);
+        } else {
+            return rest.starts_with(':');
+        }
+    }
 }
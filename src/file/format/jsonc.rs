@@ -0,0 +1,155 @@
+use std::error::Error;
+
+use crate::file::format::json;
+use crate::map::Map;
+use crate::value::Value;
+
+pub fn parse(
+    uri: Option<&String>,
+    text: &str,
+) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+    // Blank out the JSONC extensions so the text can be handled as plain JSON
+    let text = strip_trailing_commas(&strip_comments(text)?);
+    json::parse(uri, &text)
+}
+
+/// Replaces `//` and `/* */` comments with whitespace.
+///
+/// Line breaks and the length of the text are kept so positions reported by the JSON parser
+/// still point into the original text. A block comment left open is an error.
+fn strip_comments(text: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let mut output = Vec::with_capacity(text.len());
+    let mut bytes = text.bytes().peekable();
+    let mut in_string = false;
+
+    while let Some(byte) = bytes.next() {
+        if in_string {
+            output.push(byte);
+            match byte {
+                b'\\' => output.extend(bytes.next()),
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (byte, bytes.peek()) {
+            (b'"', _) => {
+                in_string = true;
+                output.push(byte);
+            }
+
+            (b'/', Some(b'/')) => {
+                output.push(b' ');
+                for byte in bytes.by_ref() {
+                    if byte == b'\n' {
+                        output.push(byte);
+                        break;
+                    }
+                    output.push(blank(byte));
+                }
+            }
+
+            (b'/', Some(b'*')) => {
+                let (line, column) = position(&output);
+                output.extend(b"  ");
+                bytes.next();
+                let mut closed = false;
+                while let Some(byte) = bytes.next() {
+                    if byte == b'*' && bytes.peek() == Some(&b'/') {
+                        output.extend(b"  ");
+                        bytes.next();
+                        closed = true;
+                        break;
+                    }
+                    output.push(blank(byte));
+                }
+
+                if !closed {
+                    return Err(format!(
+                        "unterminated block comment at line {} column {}",
+                        line, column
+                    )
+                    .into());
+                }
+            }
+
+            _ => output.push(byte),
+        }
+    }
+
+    // Comments are blanked out as a whole, so the output is still valid UTF-8
+    Ok(String::from_utf8(output).expect("stripping comments broke UTF-8"))
+}
+
+/// Returns the line and column, both from 1, of the byte following `text`.
+fn position(text: &[u8]) -> (usize, usize) {
+    let line = text.iter().filter(|&&byte| byte == b'\n').count() + 1;
+    let line_start = text
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |index| index + 1);
+    (line, text.len() - line_start + 1)
+}
+
+/// Replaces commas that follow a value and directly precede a closing `]` or `}` with
+/// whitespace.
+///
+/// A comma following no value, as in `[,]`, is kept for the JSON parser to reject.
+fn strip_trailing_commas(text: &str) -> String {
+    let mut output = text.as_bytes().to_vec();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut after_value = false;
+    let mut pending_comma = None;
+
+    for (index, &byte) in text.as_bytes().iter().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => {
+                    in_string = false;
+                    after_value = true;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b',' => {
+                pending_comma = if after_value { Some(index) } else { None };
+                after_value = false;
+            }
+            b']' | b'}' => {
+                if let Some(comma) = pending_comma.take() {
+                    output[comma] = b' ';
+                }
+                after_value = true;
+            }
+            b'[' | b'{' | b':' => {
+                pending_comma = None;
+                after_value = false;
+            }
+            _ if byte.is_ascii_whitespace() => {}
+            _ => {
+                in_string = byte == b'"';
+                // The closing quote ends a string value
+                after_value = !in_string;
+                pending_comma = None;
+            }
+        }
+    }
+
+    String::from_utf8(output).expect("stripping trailing commas broke UTF-8")
+}
+
+/// Blanks a byte of a comment, keeping line breaks so line numbers don't shift.
+fn blank(byte: u8) -> u8 {
+    if byte == b'\n' || byte == b'\r' {
+        byte
+    } else {
+        b' '
+    }
+}
@@ -0,0 +1,207 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use kdl::{KdlDocument, KdlEntry, KdlNode, KdlValue};
+
+use crate::format;
+use crate::map::Map;
+use crate::value::{Value, ValueKind};
+
+/// Name of the nodes that represent the items of a list.
+const LIST_ITEM: &str = "-";
+
+pub fn parse(
+    uri: Option<&String>,
+    text: &str,
+) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+    // Parse a KDL document from the provided text
+    let document = KdlDocument::parse(text).map_err(|err| KdlParseError::new(text, &err))?;
+    let value = Value::new(uri, from_kdl_document(uri, &document)?);
+    format::extract_root_table(uri, value)
+}
+
+/// Children of a node (or the top level nodes of a document) become a table keyed by node name.
+///
+/// Nodes sharing a name are collected into an array, and a block made only of `-` nodes is a list.
+fn from_kdl_document(
+    uri: Option<&String>,
+    document: &KdlDocument,
+) -> Result<ValueKind, Box<dyn Error + Send + Sync>> {
+    let nodes = document.nodes();
+
+    if !nodes.is_empty() && nodes.iter().all(|node| node.name().value() == LIST_ITEM) {
+        let array = nodes
+            .iter()
+            .map(|node| from_kdl_node(uri, node))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        return Ok(ValueKind::Array(array));
+    }
+
+    let mut grouped: Map<String, Vec<Value>> = Map::new();
+    for node in nodes {
+        grouped
+            .entry(node.name().value().to_owned())
+            .or_default()
+            .push(from_kdl_node(uri, node)?);
+    }
+
+    let table = grouped
+        .into_iter()
+        .map(|(key, mut values)| {
+            let value = if values.len() == 1 {
+                values.remove(0)
+            } else {
+                Value::new(uri, ValueKind::Array(values))
+            };
+
+            (key, value)
+        })
+        .collect();
+
+    Ok(ValueKind::Table(table))
+}
+
+/// A node is either a scalar (one argument), an array (several arguments) or a table built from
+/// its properties and children.
+fn from_kdl_node(
+    uri: Option<&String>,
+    node: &KdlNode,
+) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    let (properties, arguments): (Vec<&KdlEntry>, Vec<&KdlEntry>) = node
+        .entries()
+        .iter()
+        .partition(|entry| entry.name().is_some());
+
+    if properties.is_empty() && node.children().is_none() {
+        let kind = match arguments.as_slice() {
+            [] => ValueKind::Nil,
+            [argument] => from_kdl_value(argument.value()),
+            arguments => ValueKind::Array(
+                arguments
+                    .iter()
+                    .map(|argument| Value::new(uri, from_kdl_value(argument.value())))
+                    .collect(),
+            ),
+        };
+
+        return Ok(Value::new(uri, kind));
+    }
+
+    if !arguments.is_empty() {
+        return Err(Box::new(KdlStructureError::MixedArguments(
+            node.name().value().to_owned(),
+        )));
+    }
+
+    let children = match node.children() {
+        Some(children) => from_kdl_document(uri, children)?,
+        None => ValueKind::Table(Map::new()),
+    };
+
+    let kind = match children {
+        ValueKind::Table(children) => {
+            // Later properties win over earlier ones, children win over properties
+            let mut table: Map<String, Value> = properties
+                .into_iter()
+                .filter_map(|property| {
+                    let value = Value::new(uri, from_kdl_value(property.value()));
+                    property.name().map(|name| (name.value().to_owned(), value))
+                })
+                .collect();
+            table.extend(children);
+
+            ValueKind::Table(table)
+        }
+
+        array if properties.is_empty() => array,
+
+        _ => {
+            return Err(Box::new(KdlStructureError::MixedListItems(
+                node.name().value().to_owned(),
+            )))
+        }
+    };
+
+    Ok(Value::new(uri, kind))
+}
+
+fn from_kdl_value(value: &KdlValue) -> ValueKind {
+    match *value {
+        KdlValue::String(ref value) => ValueKind::String(value.clone()),
+        KdlValue::Integer(value) => match i64::try_from(value) {
+            Ok(value) => ValueKind::I64(value),
+            Err(_) => ValueKind::I128(value),
+        },
+        KdlValue::Float(value) => ValueKind::Float(value),
+        KdlValue::Bool(value) => ValueKind::Boolean(value),
+        KdlValue::Null => ValueKind::Nil,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct KdlParseError {
+    message: String,
+    line: usize,
+    column: usize,
+}
+
+impl KdlParseError {
+    fn new(text: &str, error: &kdl::KdlError) -> Self {
+        let diagnostic = error.diagnostics.first();
+        let message = diagnostic
+            .and_then(|diagnostic| diagnostic.message.clone())
+            .unwrap_or_else(|| error.to_string());
+
+        // Turn the byte offset of the diagnostic into a human friendly position
+        let before = diagnostic
+            .and_then(|diagnostic| text.get(..diagnostic.span.offset()))
+            .unwrap_or_default();
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+
+        Self {
+            message,
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for KdlParseError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            format,
+            "{} at line {} column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl Error for KdlParseError {}
+
+#[derive(Debug, Clone)]
+enum KdlStructureError {
+    MixedArguments(String),
+    MixedListItems(String),
+}
+
+impl fmt::Display for KdlStructureError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KdlStructureError::MixedArguments(ref node) => write!(
+                format,
+                "KDL node `{}` mixes arguments with properties or children",
+                node
+            ),
+            KdlStructureError::MixedListItems(ref node) => write!(
+                format,
+                "KDL node `{}` mixes properties with `-` list items",
+                node
+            ),
+        }
+    }
+}
+
+impl Error for KdlStructureError {}
@@ -7,26 +7,39 @@ use std::collections::HashMap;
 use std::error::Error;
 
 use crate::map::Map;
-use crate::{file::FileStoredFormat, value::Value, Format};
+use crate::value::{Value, ValueKind};
+use crate::{file::FileStoredFormat, Format};
 
 #[cfg(feature = "toml")]
 mod toml;
 
 #[cfg(feature = "json")]
-mod json;
+pub(crate) mod json;
+
+#[cfg(feature = "json")]
+mod jsonc;
 
 #[cfg(feature = "yaml")]
 mod yaml;
 
+#[cfg(feature = "yaml")]
+pub use self::yaml::{YamlDocuments, YamlFormat};
+
 #[cfg(feature = "ini")]
 mod ini;
 
+#[cfg(feature = "ini")]
+pub use self::ini::IniFormat;
+
 #[cfg(feature = "ron")]
 mod ron;
 
 #[cfg(feature = "json5")]
 mod json5;
 
+#[cfg(feature = "kdl")]
+mod kdl;
+
 /// File formats provided by the library.
 ///
 /// Although it is possible to define custom formats using [`Format`] trait it is recommended to use FileFormat if possible.
@@ -55,6 +68,14 @@ pub enum FileFormat {
     /// JSON5 (parsed with json5)
     #[cfg(feature = "json5")]
     Json5,
+
+    /// JSON with comments and trailing commas (parsed with serde_json)
+    #[cfg(feature = "json")]
+    Jsonc,
+
+    /// KDL (parsed with kdl)
+    #[cfg(feature = "kdl")]
+    Kdl,
 }
 
 lazy_static! {
@@ -81,8 +102,34 @@ lazy_static! {
         #[cfg(feature = "json5")]
         formats.insert(FileFormat::Json5, vec!["json5"]);
 
+        #[cfg(feature = "json")]
+        formats.insert(FileFormat::Jsonc, vec!["jsonc"]);
+
+        #[cfg(feature = "kdl")]
+        formats.insert(FileFormat::Kdl, vec!["kdl"]);
+
         formats
     };
+
+    /// Formats enabled, in the order of precedence of the files found for one base name.
+    pub(crate) static ref FORMAT_PRECEDENCE: Vec<FileFormat> = vec![
+        #[cfg(feature = "toml")]
+        FileFormat::Toml,
+        #[cfg(feature = "json")]
+        FileFormat::Json,
+        #[cfg(feature = "yaml")]
+        FileFormat::Yaml,
+        #[cfg(feature = "ini")]
+        FileFormat::Ini,
+        #[cfg(feature = "ron")]
+        FileFormat::Ron,
+        #[cfg(feature = "json5")]
+        FileFormat::Json5,
+        #[cfg(feature = "json")]
+        FileFormat::Jsonc,
+        #[cfg(feature = "kdl")]
+        FileFormat::Kdl,
+    ];
 }
 
 impl FileFormat {
@@ -117,6 +164,12 @@ impl FileFormat {
             #[cfg(feature = "json5")]
             FileFormat::Json5 => json5::parse(uri, text),
 
+            #[cfg(feature = "json")]
+            FileFormat::Jsonc => jsonc::parse(uri, text),
+
+            #[cfg(feature = "kdl")]
+            FileFormat::Kdl => kdl::parse(uri, text),
+
             #[cfg(all(
                 not(feature = "toml"),
                 not(feature = "json"),
@@ -124,6 +177,7 @@ impl FileFormat {
                 not(feature = "ini"),
                 not(feature = "ron"),
                 not(feature = "json5"),
+                not(feature = "kdl"),
             ))]
             _ => unreachable!("No features are enabled, this library won't work without features"),
         }
@@ -138,6 +192,28 @@ impl Format for FileFormat {
     ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
         self.parse(uri, text)
     }
+
+    fn parse_value(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        match self {
+            #[cfg(feature = "toml")]
+            FileFormat::Toml => toml::parse_value(uri, text),
+
+            #[cfg(feature = "json")]
+            FileFormat::Json => json::parse_value(uri, text),
+
+            #[cfg(feature = "yaml")]
+            FileFormat::Yaml => yaml::parse_value(uri, text),
+
+            #[allow(unreachable_patterns)]
+            _ => self
+                .parse(uri, text)
+                .map(|table| Value::new(uri, ValueKind::Table(table))),
+        }
+    }
 }
 
 impl FileStoredFormat for FileFormat {
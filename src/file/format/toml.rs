@@ -1,5 +1,6 @@
 use std::error::Error;
 
+use crate::datetime::Datetime;
 use crate::format;
 use crate::map::Map;
 use crate::value::Value;
@@ -13,6 +14,20 @@ pub fn parse(
     format::extract_root_table(uri, value)
 }
 
+pub fn parse_value(
+    uri: Option<&String>,
+    text: &str,
+) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    // A lone value such as `[1, 2]` or `{ a = true }` is only valid TOML on the right of a key
+    match toml::from_str::<toml::Table>(&format!("value = {}", text)) {
+        Ok(table) if table.len() == 1 => Ok(from_toml_value(uri, &table["value"])),
+        _ => {
+            let value = from_toml_value(uri, &toml::from_str(text)?);
+            Ok(value)
+        }
+    }
+}
+
 fn from_toml_value(uri: Option<&String>, value: &toml::Value) -> Value {
     match *value {
         toml::Value::String(ref value) => Value::new(uri, value.to_string()),
@@ -40,6 +55,6 @@ fn from_toml_value(uri: Option<&String>, value: &toml::Value) -> Value {
             Value::new(uri, l)
         }
 
-        toml::Value::Datetime(ref datetime) => Value::new(uri, datetime.to_string()),
+        toml::Value::Datetime(datetime) => Value::new(uri, Datetime::from(datetime)),
     }
 }
@@ -1,76 +1,700 @@
+use std::collections::HashMap;
+use std::env;
 use std::error::Error;
 use std::fmt;
-use std::mem;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use yaml_rust2 as yaml;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser, Tag};
+use yaml_rust2::scanner::Marker;
+use yaml_rust2::scanner::TScalarStyle;
 
-use crate::format;
+use crate::bytes::decode_base64;
+use crate::datetime::Datetime;
+use crate::file::{FileFormat, FileStoredFormat};
+use crate::format::{self, Format};
 use crate::map::Map;
 use crate::value::{Value, ValueKind};
 
+/// YAML format with configurable handling of the file contents.
+///
+/// [`FileFormat::Yaml`] behaves like `YamlFormat::default()`.
+///
+/// ```rust
+/// # use config::{Config, File, YamlDocuments, YamlFormat};
+/// let format = YamlFormat::new().documents(YamlDocuments::Select {
+///     key: "profile".into(),
+///     value: "production".into(),
+/// });
+///
+/// let config = Config::builder()
+///     .add_source(File::from_str(
+///         "
+/// port: 8080
+/// ---
+/// profile: production
+/// port: 80
+/// ",
+///         format,
+///     ))
+///     .build()?;
+///
+/// assert_eq!(config.get_int("port")?, 80);
+/// # Ok::<(), config::ConfigError>(())
+/// ```
+///
+/// Custom tags such as `!env HOME` are resolved once registered, either through
+/// [`with_builtin_tags`](Self::with_builtin_tags) or [`with_tag`](Self::with_tag). Nodes with
+/// an unregistered tag are read as if they were not tagged.
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct YamlFormat {
+    documents: YamlDocuments,
+    tags: Map<String, TagResolver>,
+    timestamps: bool,
+}
+
+/// Describes how a YAML file holding several `---` separated documents is turned into
+/// configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum YamlDocuments {
+    /// The file must contain at most one document, more than one is an error.
+    #[default]
+    Single,
+
+    /// Every document is a layer, merged in order so later documents override earlier ones.
+    Merge,
+
+    /// Only the document at the given (zero based) index is used.
+    Index(usize),
+
+    /// Documents are merged in order, skipping those that have the discriminator `key` set to
+    /// anything but `value`.
+    ///
+    /// Documents without the discriminator act as a base shared by all values, similar to
+    /// profiles in Spring. The key may be a dotted path such as `spring.profile`.
+    Select { key: String, value: String },
+}
+
+impl YamlFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how multiple documents within one file are handled.
+    pub fn documents(mut self, documents: YamlDocuments) -> Self {
+        self.documents = documents;
+        self
+    }
+
+    /// Reads plain scalars that look like timestamps, e.g. `2001-12-14T21:59:43Z` or
+    /// `2002-12-14`, as [`Datetime`] instead of strings.
+    ///
+    /// Scalars explicitly tagged `!!timestamp` are always read as datetimes.
+    pub fn timestamps(mut self, timestamps: bool) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Enables the built-in tags:
+    ///
+    /// - `!env VAR` is the value of the environment variable `VAR`, which must be set.
+    /// - `!env_or [VAR, default]` is the value of `VAR`, or `default` when it is not set.
+    /// - `!file path` is the content of a file without trailing line breaks, handy for secrets.
+    /// - `!include path` is the table read from another YAML file, parsed with this format.
+    ///
+    /// Relative paths are resolved against the directory of the file holding the tag.
+    pub fn with_builtin_tags(mut self) -> Self {
+        self.tags.insert("!env".into(), TagResolver::Env);
+        self.tags.insert("!env_or".into(), TagResolver::EnvOr);
+        self.tags.insert("!file".into(), TagResolver::File);
+        self.tags.insert("!include".into(), TagResolver::Include);
+        self
+    }
+
+    /// Registers a resolver for nodes tagged with `tag`, written as in YAML (e.g. `!vault`).
+    ///
+    /// The resolver receives the tagged node as a configuration value and returns the value
+    /// replacing it. A resolver registered for a built-in tag replaces the built-in one.
+    pub fn with_tag<F>(mut self, tag: &str, resolver: F) -> Self
+    where
+        F: Fn(Value) -> Result<Value, Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+    {
+        self.tags
+            .insert(tag.into(), TagResolver::Custom(Arc::new(resolver)));
+        self
+    }
+
+    fn parse_documents(
+        &self,
+        ctx: &Context<'_>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+        // Parse the YAML documents from file
+        let docs = Loader::load_from_str(text, self.timestamps)?;
+
+        match self.documents {
+            YamlDocuments::Single => match docs.len() {
+                0 => Ok(Map::new()),
+                1 => from_yaml_document(ctx, &docs[0]),
+                n => Err(Box::new(MultipleDocumentsError(n))),
+            },
+
+            YamlDocuments::Merge => docs.iter().try_fold(Map::new(), |mut base, doc| {
+                merge_tables(&mut base, from_yaml_document(ctx, doc)?);
+                Ok(base)
+            }),
+
+            YamlDocuments::Index(index) => match docs.get(index) {
+                Some(doc) => from_yaml_document(ctx, doc),
+                None => Err(Box::new(DocumentIndexError {
+                    index,
+                    count: docs.len(),
+                })),
+            },
+
+            YamlDocuments::Select { ref key, ref value } => {
+                docs.iter().try_fold(Map::new(), |mut base, doc| {
+                    let doc = from_yaml_document(ctx, doc)?;
+                    let selected = match lookup(&doc, key) {
+                        Some(discriminator) => {
+                            discriminator.clone().into_string().ok().as_ref() == Some(value)
+                        }
+                        None => true,
+                    };
+
+                    if selected {
+                        merge_tables(&mut base, doc);
+                    }
+
+                    Ok(base)
+                })
+            }
+        }
+    }
+}
+
+impl Format for YamlFormat {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+        let includes: Vec<PathBuf> = uri.map(canonical_path).into_iter().collect();
+        let ctx = Context {
+            format: self,
+            uri,
+            includes: &includes,
+        };
+
+        self.parse_documents(&ctx, text)
+    }
+
+    fn parse_value(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        let includes: Vec<PathBuf> = uri.map(canonical_path).into_iter().collect();
+        let ctx = Context {
+            format: self,
+            uri,
+            includes: &includes,
+        };
+
+        let docs = Loader::load_from_str(text, self.timestamps)?;
+        match docs.len() {
+            0 => Ok(Value::new(uri, ValueKind::Nil)),
+            1 => from_yaml_value(&ctx, &docs[0]),
+            n => Err(Box::new(MultipleDocumentsError(n))),
+        }
+    }
+}
+
+impl FileStoredFormat for YamlFormat {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        FileFormat::Yaml.file_extensions()
+    }
+}
+
 pub fn parse(
     uri: Option<&String>,
     text: &str,
 ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
-    // Parse a YAML object from file
-    let mut docs = yaml::YamlLoader::load_from_str(text)?;
-    let root = match docs.len() {
-        0 => yaml::Yaml::Hash(yaml::yaml::Hash::new()),
-        1 => mem::replace(&mut docs[0], yaml::Yaml::Null),
-        n => {
-            return Err(Box::new(MultipleDocumentsError(n)));
-        }
-    };
-
-    let value = from_yaml_value(uri, &root)?;
-    format::extract_root_table(uri, value)
+    YamlFormat::default().parse(uri, text)
 }
 
-fn from_yaml_value(
+pub fn parse_value(
     uri: Option<&String>,
-    value: &yaml::Yaml,
+    text: &str,
 ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    YamlFormat::default().parse_value(uri, text)
+}
+
+type TagFn = dyn Fn(Value) -> Result<Value, Box<dyn Error + Send + Sync>> + Send + Sync;
+
+/// Resolves a custom tag, the built-in ones need access to the format and the file being read.
+#[derive(Clone)]
+enum TagResolver {
+    Env,
+    EnvOr,
+    File,
+    Include,
+    Custom(Arc<TagFn>),
+}
+
+impl fmt::Debug for TagResolver {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TagResolver::Env => write!(f, "Env"),
+            TagResolver::EnvOr => write!(f, "EnvOr"),
+            TagResolver::File => write!(f, "File"),
+            TagResolver::Include => write!(f, "Include"),
+            TagResolver::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// State shared while converting the documents of one file.
+struct Context<'a> {
+    format: &'a YamlFormat,
+    uri: Option<&'a String>,
+
+    /// Canonical paths of the files being read through `!include`, to detect cycles
+    includes: &'a [PathBuf],
+}
+
+/// A YAML node, like [`yaml::Yaml`] but keeping custom tags around.
+#[derive(Debug, Clone)]
+enum Node {
+    Scalar(yaml::Yaml),
+    Array(Vec<Node>),
+    Hash(Vec<(Node, Node)>),
+    Tagged(String, Box<Node>),
+    /// A plain, untagged `<<`, which merges its value into the mapping when used as a key.
+    MergeKey,
+    Datetime(Datetime),
+    Bytes(Vec<u8>),
+}
+
+/// Builds [`Node`]s from parser events, in the same way as [`yaml::YamlLoader`].
+#[derive(Default)]
+struct Loader {
+    docs: Vec<Node>,
+    stack: Vec<(Node, usize, Option<String>)>,
+    keys: Vec<Option<Node>>,
+    anchors: HashMap<usize, Node>,
+    timestamps: bool,
+}
+
+/// Handle of the tags defined by the YAML core schema, such as `!!int`.
+const CORE_SCHEMA: &str = "tag:yaml.org,2002:";
+
+impl Loader {
+    fn load_from_str(text: &str, timestamps: bool) -> Result<Vec<Node>, yaml::ScanError> {
+        let mut loader = Self {
+            timestamps,
+            ..Self::default()
+        };
+        Parser::new(text.chars()).load(&mut loader, true)?;
+        Ok(loader.docs)
+    }
+
+    fn insert(&mut self, node: Node, anchor: usize) {
+        // Valid anchor ids start from 1
+        if anchor > 0 {
+            self.anchors.insert(anchor, node.clone());
+        }
+
+        match self.stack.last_mut() {
+            None => self.stack.push((node, 0, None)),
+            Some((Node::Array(ref mut array), ..)) => array.push(node),
+            Some((Node::Hash(ref mut hash), ..)) => {
+                let key = self.keys.last_mut().expect("mapping without key slot");
+                match key.take() {
+                    Some(key) => hash.push((key, node)),
+                    None => *key = Some(node),
+                }
+            }
+            Some(_) => unreachable!(),
+        }
+    }
+
+    fn end_collection(&mut self) {
+        let (node, anchor, tag) = self.stack.pop().expect("unbalanced collection");
+        self.insert(tagged(tag, node), anchor);
+    }
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, event: Event, _: Marker) {
+        match event {
+            Event::DocumentStart | Event::Nothing | Event::StreamStart | Event::StreamEnd => {}
+
+            Event::DocumentEnd => match self.stack.pop() {
+                // Empty document
+                None => self.docs.push(Node::Scalar(yaml::Yaml::BadValue)),
+                Some((node, ..)) => self.docs.push(node),
+            },
+
+            Event::SequenceStart(anchor, tag) => {
+                self.stack
+                    .push((Node::Array(Vec::new()), anchor, custom_tag(tag)));
+            }
+
+            Event::MappingStart(anchor, tag) => {
+                self.stack
+                    .push((Node::Hash(Vec::new()), anchor, custom_tag(tag)));
+                self.keys.push(None);
+            }
+
+            Event::SequenceEnd => self.end_collection(),
+
+            Event::MappingEnd => {
+                self.keys.pop();
+                self.end_collection();
+            }
+
+            Event::Scalar(value, style, anchor, tag) => {
+                let plain = style == TScalarStyle::Plain;
+                let explicit_timestamp = matches!(
+                    tag,
+                    Some(ref tag) if tag.handle == CORE_SCHEMA && tag.suffix == "timestamp"
+                );
+                let timestamp = plain && (explicit_timestamp || tag.is_none() && self.timestamps);
+
+                let scalar = if !plain {
+                    yaml::Yaml::String(value)
+                } else {
+                    match tag {
+                        Some(ref tag) if tag.handle == CORE_SCHEMA => {
+                            core_scalar(&tag.suffix, value)
+                        }
+                        Some(_) => yaml::Yaml::String(value),
+                        None => yaml::Yaml::from_str(&value),
+                    }
+                };
+
+                let binary = matches!(
+                    tag,
+                    Some(ref tag) if tag.handle == CORE_SCHEMA && tag.suffix == "binary"
+                );
+
+                let node = match scalar {
+                    yaml::Yaml::String(ref value)
+                        if plain && tag.is_none() && value == MERGE_KEY =>
+                    {
+                        Node::MergeKey
+                    }
+                    // `!!binary` holds base64, usually spread over the lines of a block scalar
+                    yaml::Yaml::String(ref value) if binary => match decode_base64(value) {
+                        Some(bytes) => Node::Bytes(bytes),
+                        None => Node::Scalar(yaml::Yaml::BadValue),
+                    },
+                    yaml::Yaml::String(ref value) if timestamp => match value.parse() {
+                        Ok(datetime) => Node::Datetime(datetime),
+                        Err(_) if explicit_timestamp => Node::Scalar(yaml::Yaml::BadValue),
+                        Err(_) => Node::Scalar(scalar),
+                    },
+                    _ => Node::Scalar(scalar),
+                };
+
+                self.insert(tagged(custom_tag(tag), node), anchor);
+            }
+
+            Event::Alias(anchor) => {
+                let node = self
+                    .anchors
+                    .get(&anchor)
+                    .cloned()
+                    .unwrap_or(Node::Scalar(yaml::Yaml::BadValue));
+                self.insert(node, 0);
+            }
+        }
+    }
+}
+
+/// Returns the name of a tag outside of the core schema, such as `!env`.
+fn custom_tag(tag: Option<Tag>) -> Option<String> {
+    tag.filter(|tag| tag.handle != CORE_SCHEMA)
+        .map(|tag| tag.handle + &tag.suffix)
+}
+
+fn tagged(tag: Option<String>, node: Node) -> Node {
+    match tag {
+        Some(tag) => Node::Tagged(tag, Box::new(node)),
+        None => node,
+    }
+}
+
+/// Reads a plain scalar explicitly tagged with a core schema type, as in `!!int 3`.
+fn core_scalar(suffix: &str, value: String) -> yaml::Yaml {
+    match suffix {
+        "bool" => value
+            .parse::<bool>()
+            .map_or(yaml::Yaml::BadValue, yaml::Yaml::Boolean),
+        "int" => value
+            .parse::<i64>()
+            .map_or(yaml::Yaml::BadValue, yaml::Yaml::Integer),
+        "float" => match yaml::Yaml::from_str(&value) {
+            yaml::Yaml::Real(_) | yaml::Yaml::Integer(_) => yaml::Yaml::Real(value),
+            _ => yaml::Yaml::BadValue,
+        },
+        "null" => match value.as_ref() {
+            "~" | "null" => yaml::Yaml::Null,
+            _ => yaml::Yaml::BadValue,
+        },
+        _ => yaml::Yaml::String(value),
+    }
+}
+
+/// Converts a single document, an empty document is an empty table.
+fn from_yaml_document(
+    ctx: &Context<'_>,
+    doc: &Node,
+) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+    if let Node::Scalar(yaml::Yaml::BadValue) = doc {
+        return Ok(Map::new());
+    }
+
+    let value = from_yaml_value(ctx, doc)?;
+    format::extract_root_table(ctx.uri, value)
+}
+
+/// Key of a mapping entry whose value is merged into the mapping, as in `<<: *base`.
+const MERGE_KEY: &str = "<<";
+
+/// Deep merges `layer` into `base`, tables are merged key by key while everything else is
+/// replaced.
+fn merge_tables(base: &mut Map<String, Value>, layer: Map<String, Value>) {
+    for (key, value) in layer {
+        match base.get_mut(&key) {
+            Some(Value {
+                kind: ValueKind::Table(existing),
+                ..
+            }) if matches!(value.kind, ValueKind::Table(_)) => {
+                if let ValueKind::Table(table) = value.kind {
+                    merge_tables(existing, table);
+                }
+            }
+
+            _ => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn lookup<'a>(table: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    let mut segments = key.split('.');
+    let mut value = table.get(segments.next()?)?;
+
+    for segment in segments {
+        match value.kind {
+            ValueKind::Table(ref table) => value = table.get(segment)?,
+            _ => return None,
+        }
+    }
+
+    Some(value)
+}
+
+fn from_yaml_value(ctx: &Context<'_>, value: &Node) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    let uri = ctx.uri;
     match *value {
-        yaml::Yaml::String(ref value) => Ok(Value::new(uri, ValueKind::String(value.clone()))),
-        yaml::Yaml::Real(ref value) => {
-            // TODO: Figure out in what cases this can panic?
-            value
-                .parse::<f64>()
-                .map_err(|_| {
-                    Box::new(FloatParsingError(value.to_string())) as Box<(dyn Error + Send + Sync)>
-                })
-                .map(ValueKind::Float)
-                .map(|f| Value::new(uri, f))
+        Node::Scalar(yaml::Yaml::String(ref value)) => {
+            Ok(Value::new(uri, ValueKind::String(value.clone())))
         }
-        yaml::Yaml::Integer(value) => Ok(Value::new(uri, ValueKind::I64(value))),
-        yaml::Yaml::Boolean(value) => Ok(Value::new(uri, ValueKind::Boolean(value))),
-        yaml::Yaml::Hash(ref table) => {
+        // Integers too large for `Integer` end up here as well
+        Node::Scalar(yaml::Yaml::Real(ref value)) => ValueKind::parse_number(value)
+            .map(|kind| Value::new(uri, kind))
+            .ok_or_else(|| Box::new(FloatParsingError(value.to_string())) as _),
+        Node::Scalar(yaml::Yaml::Integer(value)) => Ok(Value::new(uri, ValueKind::I64(value))),
+        Node::Scalar(yaml::Yaml::Boolean(value)) => Ok(Value::new(uri, ValueKind::Boolean(value))),
+        Node::Hash(ref table) => {
             let mut m = Map::new();
+            let mut merges = Vec::new();
             for (key, value) in table {
                 match key {
-                    yaml::Yaml::String(k) => m.insert(k.to_owned(), from_yaml_value(uri, value)?),
-                    yaml::Yaml::Integer(k) => m.insert(k.to_string(), from_yaml_value(uri, value)?),
-                    _ => unreachable!(),
+                    Node::MergeKey => merges.push(value),
+                    _ => {
+                        m.insert(from_yaml_key(ctx, key)?, from_yaml_value(ctx, value)?);
+                    }
                 };
             }
+
+            // Keys of the mapping itself win over merged ones, and earlier merged mappings
+            // win over later ones
+            for merge in merges {
+                for (key, value) in merged_tables(ctx, merge)?.into_iter().flatten() {
+                    m.entry(key).or_insert(value);
+                }
+            }
+
             Ok(Value::new(uri, ValueKind::Table(m)))
         }
-        yaml::Yaml::Array(ref array) => {
+        Node::Array(ref array) => {
             let mut l = Vec::new();
 
             for value in array {
-                l.push(from_yaml_value(uri, value)?);
+                l.push(from_yaml_value(ctx, value)?);
             }
 
             Ok(Value::new(uri, ValueKind::Array(l)))
         }
 
-        // 1. Yaml NULL
-        // 2. BadValue – It shouldn't be possible to hit BadValue as this only happens when
-        //               using the index trait badly or on a type error but we send back nil.
-        // 3. Alias – No idea what to do with this and there is a note in the lib that its
-        //            not fully supported yet anyway
-        _ => Ok(Value::new(uri, ValueKind::Nil)),
+        Node::Tagged(ref tag, ref node) => {
+            let value = from_yaml_value(ctx, node)?;
+            match ctx.format.tags.get(tag) {
+                Some(resolver) => resolve_tag(ctx, tag, resolver, value),
+                None => Ok(value),
+            }
+        }
+
+        Node::MergeKey => Ok(Value::new(uri, ValueKind::String(MERGE_KEY.to_owned()))),
+
+        Node::Datetime(datetime) => Ok(Value::new(uri, ValueKind::Datetime(datetime))),
+
+        Node::Bytes(ref bytes) => Ok(Value::new(uri, ValueKind::Bytes(bytes.clone()))),
+
+        Node::Scalar(yaml::Yaml::Null) => Ok(Value::new(uri, ValueKind::Nil)),
+
+        // Aliases are replaced by the anchored node while loading, so whatever is left either
+        // refers to an anchor that was not (fully) defined yet or has a value not matching its
+        // `!!type` tag
+        Node::Scalar(_) => Err(Box::new(BadValueError)),
+    }
+}
+
+/// Converts a mapping key to a string, scalars other than strings are written in their canonical
+/// form, e.g. `true`, `1` or `null`.
+fn from_yaml_key(ctx: &Context<'_>, key: &Node) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let key = from_yaml_value(ctx, key)?;
+    match key.kind {
+        ValueKind::Nil => Ok("null".to_owned()),
+        // The `.` of a float would split it into a path of two keys
+        ValueKind::Float(value) => Err(Box::new(FloatKeyError(format!("{:?}", value)))),
+        ValueKind::Decimal(value) => Err(Box::new(FloatKeyError(value))),
+        ValueKind::Table(_) => Err(Box::new(ComplexKeyError("mapping"))),
+        ValueKind::Array(_) => Err(Box::new(ComplexKeyError("sequence"))),
+        ValueKind::Bytes(_) => Err(Box::new(ComplexKeyError("binary value"))),
+        kind => Ok(kind.to_string()),
+    }
+}
+
+/// Resolves the value of a `<<` merge key to the mappings to merge, in order of precedence.
+fn merged_tables(
+    ctx: &Context<'_>,
+    value: &Node,
+) -> Result<Vec<Map<String, Value>>, Box<dyn Error + Send + Sync>> {
+    let values = match *value {
+        Node::Array(ref array) => array.iter().collect(),
+        _ => vec![value],
+    };
+
+    values
+        .into_iter()
+        .map(|value| match from_yaml_value(ctx, value)?.kind {
+            ValueKind::Table(table) => Ok(table),
+            _ => Err(Box::new(MergeKeyError) as Box<dyn Error + Send + Sync>),
+        })
+        .collect()
+}
+
+fn resolve_tag(
+    ctx: &Context<'_>,
+    tag: &str,
+    resolver: &TagResolver,
+    value: Value,
+) -> Result<Value, Box<dyn Error + Send + Sync>> {
+    let uri = ctx.uri;
+    let string_argument = |value: Value| {
+        value
+            .into_string()
+            .map_err(|_| TagError::Argument(tag.to_owned(), "a string"))
+    };
+
+    match *resolver {
+        TagResolver::Env => {
+            let name = string_argument(value)?;
+            match env::var(&name) {
+                Ok(var) => Ok(Value::new(uri, ValueKind::String(var))),
+                Err(_) => Err(Box::new(TagError::MissingVariable(name))),
+            }
+        }
+
+        TagResolver::EnvOr => {
+            let mut arguments = match value.into_array() {
+                Ok(arguments) if arguments.len() == 2 => arguments,
+                _ => {
+                    return Err(Box::new(TagError::Argument(
+                        tag.to_owned(),
+                        "a variable name and a default value",
+                    )))
+                }
+            };
+
+            let default = arguments.pop().expect("two arguments");
+            let name = string_argument(arguments.pop().expect("two arguments"))?;
+            match env::var(name) {
+                Ok(var) => Ok(Value::new(uri, ValueKind::String(var))),
+                Err(_) => Ok(default),
+            }
+        }
+
+        TagResolver::File => {
+            let path = relative_path(ctx, &string_argument(value)?);
+            let content = fs::read_to_string(&path).map_err(|err| TagError::Io(path, err))?;
+            let content = content.trim_end_matches(&['\n', '\r'][..]).to_owned();
+
+            Ok(Value::new(uri, ValueKind::String(content)))
+        }
+
+        TagResolver::Include => {
+            let path = relative_path(ctx, &string_argument(value)?);
+            let canonical =
+                fs::canonicalize(&path).map_err(|err| TagError::Io(path.clone(), err))?;
+            if ctx.includes.contains(&canonical) {
+                return Err(Box::new(TagError::IncludeCycle(path)));
+            }
+
+            let text = fs::read_to_string(&path).map_err(|err| TagError::Io(path.clone(), err))?;
+            let included = path.to_string_lossy().into_owned();
+            let includes = [ctx.includes, std::slice::from_ref(&canonical)].concat();
+            let table = ctx
+                .format
+                .parse_documents(
+                    &Context {
+                        format: ctx.format,
+                        uri: Some(&included),
+                        includes: &includes,
+                    },
+                    &text,
+                )
+                .map_err(|cause| TagError::Include(path, cause))?;
+
+            Ok(Value::new(Some(&included), ValueKind::Table(table)))
+        }
+
+        TagResolver::Custom(ref resolver) => {
+            resolver(value).map(|resolved| Value::new(uri, resolved.kind))
+        }
+    }
+}
+
+/// Resolves the path of the file being read, so differently spelled paths to it compare equal.
+fn canonical_path(uri: &String) -> PathBuf {
+    fs::canonicalize(uri).unwrap_or_else(|_| PathBuf::from(uri))
+}
+
+/// Resolves a path relative to the directory of the file being read.
+fn relative_path(ctx: &Context<'_>, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    match ctx.uri.and_then(|uri| Path::new(uri).parent()) {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path.to_owned(),
     }
 }
 
@@ -89,6 +713,24 @@ impl Error for MultipleDocumentsError {
     }
 }
 
+#[derive(Debug, Copy, Clone)]
+struct DocumentIndexError {
+    index: usize,
+    count: usize,
+}
+
+impl fmt::Display for DocumentIndexError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            format,
+            "YAML document {} requested, but only {} found",
+            self.index, self.count
+        )
+    }
+}
+
+impl Error for DocumentIndexError {}
+
 #[derive(Debug, Clone)]
 struct FloatParsingError(String);
 
@@ -103,3 +745,94 @@ impl Error for FloatParsingError {
         "Floating point number parsing failed"
     }
 }
+
+#[derive(Debug, Copy, Clone)]
+struct BadValueError;
+
+impl fmt::Display for BadValueError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            format,
+            "YAML alias refers to an undefined anchor, or a value does not match its tag"
+        )
+    }
+}
+
+impl Error for BadValueError {}
+
+#[derive(Debug, Copy, Clone)]
+struct ComplexKeyError(&'static str);
+
+impl fmt::Display for ComplexKeyError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            format,
+            "YAML mapping keys must be scalars, found a {} as key",
+            self.0
+        )
+    }
+}
+
+impl Error for ComplexKeyError {}
+
+#[derive(Debug, Clone)]
+struct FloatKeyError(String);
+
+impl fmt::Display for FloatKeyError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            format,
+            "YAML mapping key `{}` is a float, which cannot be a key as its `.` separates keys",
+            self.0
+        )
+    }
+}
+
+impl Error for FloatKeyError {}
+
+#[derive(Debug, Copy, Clone)]
+struct MergeKeyError;
+
+impl fmt::Display for MergeKeyError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            format,
+            "YAML merge key `<<` expects a mapping or a sequence of mappings"
+        )
+    }
+}
+
+impl Error for MergeKeyError {}
+
+#[derive(Debug)]
+enum TagError {
+    Argument(String, &'static str),
+    MissingVariable(String),
+    Io(PathBuf, std::io::Error),
+    IncludeCycle(PathBuf),
+    Include(PathBuf, Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for TagError {
+    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TagError::Argument(ref tag, expected) => {
+                write!(format, "YAML tag `{}` expects {}", tag, expected)
+            }
+            TagError::MissingVariable(ref name) => {
+                write!(format, "environment variable `{}` is not set", name)
+            }
+            TagError::Io(ref path, ref err) => {
+                write!(format, "reading {} failed: {}", path.display(), err)
+            }
+            TagError::IncludeCycle(ref path) => {
+                write!(format, "`!include` of {} forms a cycle", path.display())
+            }
+            TagError::Include(ref path, ref cause) => {
+                write!(format, "{} in {}", cause, path.display())
+            }
+        }
+    }
+}
+
+impl Error for TagError {}
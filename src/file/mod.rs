@@ -1,19 +1,30 @@
-mod format;
+mod compression;
+pub(crate) mod format;
+mod registry;
+mod sniff;
 pub mod source;
 
 use std::fmt::Debug;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
 use crate::error::{ConfigError, Result};
 use crate::map::Map;
-use crate::source::Source;
-use crate::value::Value;
+use crate::source::{set_value, Source};
+use crate::value::{Value, ValueKind};
 use crate::Format;
 
 pub use self::format::FileFormat;
-use self::source::FileSource;
+#[cfg(feature = "ini")]
+pub use self::format::IniFormat;
+#[cfg(feature = "yaml")]
+pub use self::format::{YamlDocuments, YamlFormat};
+pub use self::registry::{register_format, unregister_format, RegisteredFormat};
+use self::source::{FileSource, FileSourceResult};
 
-pub use self::source::file::FileSourceFile;
+pub use self::source::bytes::FileSourceBytes;
+pub use self::source::file::{FileLookupError, FileSourceFile, SearchPolicy};
+pub use self::source::reader::FileSourceReader;
 pub use self::source::string::FileSourceString;
 
 /// A configuration source backed up by a file.
@@ -52,6 +63,47 @@ where
     }
 }
 
+impl<F> File<source::bytes::FileSourceBytes, F>
+where
+    F: FileStoredFormat + 'static,
+{
+    /// Reads a file from bytes in memory, labelled `uri` in errors.
+    ///
+    /// Convert a [`FileSourceBytes`] into a `File` to detect the format from the content.
+    pub fn from_bytes<B: Into<Vec<u8>>>(uri: &str, bytes: B, format: F) -> Self {
+        Self {
+            format: Some(format),
+            required: true,
+            source: source::bytes::FileSourceBytes::new(uri, bytes),
+        }
+    }
+}
+
+impl<F> File<source::reader::FileSourceReader, F>
+where
+    F: FileStoredFormat + 'static,
+{
+    /// Reads a file from `reader`, labelled `uri` in errors.
+    ///
+    /// Convert a [`FileSourceReader`] into a `File` to detect the format from the content.
+    pub fn from_reader<R: Read + Send + 'static>(uri: &str, reader: R, format: F) -> Self {
+        Self {
+            format: Some(format),
+            required: true,
+            source: source::reader::FileSourceReader::new(uri, reader),
+        }
+    }
+
+    /// Reads a file from the standard input, labelled `-` in errors.
+    pub fn from_stdin(format: F) -> Self {
+        Self {
+            format: Some(format),
+            required: true,
+            source: source::reader::FileSourceReader::stdin(),
+        }
+    }
+}
+
 impl<F> File<source::file::FileSourceFile, F>
 where
     F: FileStoredFormat + 'static,
@@ -65,9 +117,110 @@ where
     }
 }
 
+impl<F> File<source::file::FileSourceFile, F>
+where
+    F: FileStoredFormat + 'static,
+{
+    /// Adds a directory to look for a file given by a relative path in, instead of the current
+    /// directory.
+    ///
+    /// Directories are searched in the order they are added, such as the directory of the
+    /// executable, `$XDG_CONFIG_HOME/app`, `/etc/xdg/app`, `/etc/app` and then the current
+    /// directory, given as `.`. The [`search_policy`](Self::search_policy) decides whether the
+    /// first file found is used, or all of them are merged.
+    ///
+    /// ```rust,no_run
+    /// # use config::{Config, File, SearchPolicy};
+    /// let config = Config::builder()
+    ///     .add_source(
+    ///         File::with_name("settings")
+    ///             .search_path(".")
+    ///             .search_path("/etc/app")
+    ///             .search_policy(SearchPolicy::MergeAll),
+    ///     )
+    ///     .build()?;
+    /// # Ok::<(), config::ConfigError>(())
+    /// ```
+    pub fn search_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.source.add_search_path(path.into());
+        self
+    }
+
+    /// Adds several directories, as [`search_path`](Self::search_path) does.
+    pub fn search_paths<I, P>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        for path in paths {
+            self.source.add_search_path(path.into());
+        }
+        self
+    }
+
+    /// Sets how a file found in several search paths is used, the first one by default.
+    pub fn search_policy(mut self, policy: SearchPolicy) -> Self {
+        self.source.set_search_policy(policy);
+        self
+    }
+
+    /// Sets which of the files found with the same base name and the extensions of different
+    /// formats is used, such as `settings.toml` and `settings.yaml`.
+    ///
+    /// The formats given are preferred in order over the others, which otherwise follow the
+    /// order of [`FileFormat`], starting with TOML, and then the order of
+    /// [registration](crate::register_format). A format given stands for the formats detected
+    /// that share one of its extensions, so that a custom format registered can be given as
+    /// well. The extensions of one format, as `yaml` and `yml`, are preferred in the order of
+    /// [`FileStoredFormat::file_extensions`].
+    ///
+    /// ```rust
+    /// # use config::{File, FileFormat};
+    /// let file = File::with_name("settings").format_precedence(&[&FileFormat::Yaml]);
+    /// ```
+    pub fn format_precedence(mut self, formats: &[&dyn FileStoredFormat]) -> Self {
+        self.source.set_format_precedence(formats);
+        self
+    }
+
+    /// Set to true to fail when several files are found for the base name, instead of using
+    /// the one of the [format with precedence](Self::format_precedence).
+    ///
+    /// The error is a [`FileLookupError::Ambiguous`], even when the file is not required.
+    /// Otherwise the other files are listed by
+    /// [`FileSourceResult::ambiguous_matches`](crate::FileSourceResult::ambiguous_matches).
+    pub fn error_on_ambiguity(mut self, error: bool) -> Self {
+        self.source.set_error_on_ambiguity(error);
+        self
+    }
+
+    /// Set to true to detect the format of a file whose extension is missing or not registered,
+    /// such as `/etc/myapp/config`, from its content.
+    ///
+    /// A format named by a shebang or modeline on the first lines is used, as in
+    /// `#!/usr/bin/env yaml`, `# -*- mode: toml -*-` or `# vim: set ft=toml:`. Otherwise formats
+    /// are tried [in order](Self::format_precedence), those suggested by a leading `{`, `---` or
+    /// `(` first, until one parses the content. The format detected is given by
+    /// [`FileSourceResult::detected_format`](crate::FileSourceResult::detected_format).
+    pub fn detect_format(mut self, detect: bool) -> Self {
+        self.source.set_detect_format(detect);
+        self
+    }
+
+    /// Returns the paths the file is looked for at, before any extension is added, which can
+    /// help explain where a missing file was expected.
+    pub fn candidates(&self) -> Vec<PathBuf> {
+        self.source.candidates()
+    }
+}
+
 impl File<source::file::FileSourceFile, FileFormat> {
     /// Given the basename of a file, will attempt to locate a file by setting its
     /// extension to a registered format.
+    ///
+    /// With the `gzip` or `zstd` feature, the file is also looked for compressed, as
+    /// `routes.json.gz` or `routes.json.zst`, and the extension before that of the compression
+    /// gives the format. Any file starting with the magic bytes of gzip or zstd is decompressed.
     pub fn with_name(name: &str) -> Self {
         Self {
             format: None,
@@ -97,6 +250,26 @@ impl From<PathBuf> for File<source::file::FileSourceFile, FileFormat> {
     }
 }
 
+impl From<FileSourceBytes> for File<source::bytes::FileSourceBytes, FileFormat> {
+    fn from(source: FileSourceBytes) -> Self {
+        Self {
+            format: None,
+            required: true,
+            source,
+        }
+    }
+}
+
+impl From<FileSourceReader> for File<source::reader::FileSourceReader, FileFormat> {
+    fn from(source: FileSourceReader) -> Self {
+        Self {
+            format: None,
+            required: true,
+            source,
+        }
+    }
+}
+
 impl<T, F> File<T, F>
 where
     F: FileStoredFormat + 'static,
@@ -112,6 +285,16 @@ where
         self.required = required;
         self
     }
+
+    /// Finds and reads the file without parsing it, telling which file and format are used.
+    pub fn resolve(&self) -> Result<FileSourceResult>
+    where
+        F: Clone,
+    {
+        self.source
+            .resolve(self.format.clone())
+            .map_err(ConfigError::Foreign)
+    }
 }
 
 impl<T, F> Source for File<T, F>
@@ -125,15 +308,22 @@ where
 
     fn collect(&self) -> Result<Map<String, Value>> {
         // Coerce the file contents to a string
-        let (uri, contents, format) = match self
+        let results = match self
             .source
-            .resolve(self.format.clone())
+            .resolve_all(self.format.clone())
             .map_err(ConfigError::Foreign)
         {
-            Ok(result) => (result.uri, result.content, result.format),
+            Ok(results) => results,
 
             Err(error) => {
-                if !self.required {
+                // An optional file that is ambiguous is still an error
+                let ambiguous = matches!(
+                    &error,
+                    ConfigError::Foreign(cause)
+                        if matches!(cause.downcast_ref(), Some(FileLookupError::Ambiguous { .. }))
+                );
+
+                if !self.required && !ambiguous {
                     return Ok(Map::new());
                 }
 
@@ -141,9 +331,37 @@ where
             }
         };
 
-        // Parse the string using the given format
-        format
-            .parse(uri.as_ref(), &contents)
-            .map_err(|cause| ConfigError::FileParse { uri, cause })
+        // Parse the strings using the given formats
+        let mut maps = results.into_iter().map(|result| {
+            let FileSourceResult {
+                uri,
+                content,
+                format,
+                ..
+            } = result;
+
+            format
+                .parse(uri.as_ref(), &content)
+                .map_err(|cause| ConfigError::FileParse { uri, cause })
+        });
+
+        let first = maps.next().transpose()?.unwrap_or_default();
+        let rest: Vec<_> = maps.collect::<Result<_>>()?;
+        if rest.is_empty() {
+            return Ok(first);
+        }
+
+        // Merge the files found in several search paths in order
+        let mut merged = Value::new(None, ValueKind::Table(Map::new()));
+        for map in std::iter::once(first).chain(rest) {
+            for (key, value) in &map {
+                set_value(&mut merged, key, value);
+            }
+        }
+
+        match merged.kind {
+            ValueKind::Table(map) => Ok(map),
+            _ => unreachable!("merging into a table keeps it a table"),
+        }
     }
 }
@@ -0,0 +1,157 @@
+use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+
+use crate::file::format::FORMAT_PRECEDENCE;
+use crate::file::FileStoredFormat;
+use crate::map::Map;
+use crate::value::Value;
+use crate::Format;
+
+/// A format detected from the extension of a file.
+pub(crate) type DetectedFormat = Arc<dyn FileStoredFormat + Send + Sync>;
+
+lazy_static! {
+    static ref CUSTOM_FORMATS: RwLock<Vec<(RegisteredFormat, DetectedFormat)>> =
+        RwLock::new(Vec::new());
+}
+
+static NEXT_REGISTRATION: AtomicUsize = AtomicUsize::new(0);
+
+/// A format added by [`register_format`], which [`unregister_format`] removes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegisteredFormat(usize);
+
+/// Registers a custom format, so that files with one of its
+/// [extensions](FileStoredFormat::file_extensions) are detected by [`File::with_name`] and the
+/// conversions of a path into a [`File`].
+///
+/// Formats registered are detected after the formats provided by the library, in the order they
+/// were registered, so they cannot take over an extension the library already detects, unless
+/// given a [precedence](crate::File::format_precedence). The registry is shared by the whole
+/// process until the format is [unregistered](unregister_format).
+///
+/// ```rust
+/// # use config::{Config, File, FileStoredFormat, Format, Map, Value, ValueKind};
+/// # use std::error::Error;
+/// #[derive(Debug, Clone)]
+/// struct PemFile;
+///
+/// impl Format for PemFile {
+///     fn parse(
+///         &self,
+///         uri: Option<&String>,
+///         text: &str,
+///     ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+///         let mut map = Map::new();
+///         map.insert("key".into(), Value::new(uri, ValueKind::String(text.into())));
+///         Ok(map)
+///     }
+/// }
+///
+/// impl FileStoredFormat for PemFile {
+///     fn file_extensions(&self) -> &'static [&'static str] {
+///         &["pem"]
+///     }
+/// }
+///
+/// let registered = config::register_format(PemFile);
+///
+/// // Finds `public.pem`
+/// let file = File::with_name("examples/custom_file_format/files/public");
+///
+/// config::unregister_format(registered);
+/// ```
+///
+/// [`File`]: crate::File
+/// [`File::with_name`]: crate::File::with_name
+pub fn register_format<F>(format: F) -> RegisteredFormat
+where
+    F: FileStoredFormat + Send + Sync + 'static,
+{
+    let registered = RegisteredFormat(NEXT_REGISTRATION.fetch_add(1, Ordering::Relaxed));
+    CUSTOM_FORMATS
+        .write()
+        .unwrap_or_else(|err| err.into_inner())
+        .push((registered, Arc::new(format)));
+    registered
+}
+
+/// Removes a format added by [`register_format`], so that its extensions are no longer
+/// detected.
+///
+/// Returns whether the format was still registered.
+pub fn unregister_format(registered: RegisteredFormat) -> bool {
+    let mut formats = CUSTOM_FORMATS
+        .write()
+        .unwrap_or_else(|err| err.into_inner());
+    let count = formats.len();
+    formats.retain(|(id, _)| *id != registered);
+    formats.len() < count
+}
+
+/// Returns the custom formats registered, in order of registration.
+pub(crate) fn custom_formats() -> Vec<DetectedFormat> {
+    CUSTOM_FORMATS
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .iter()
+        .map(|(_, format)| format.clone())
+        .collect()
+}
+
+/// Returns the formats to detect, in order of precedence: those sharing an extension with the
+/// formats given, in order, then the other formats of the library and the custom formats
+/// registered.
+pub(crate) fn detectable_formats(precedence: &[&'static [&'static str]]) -> Vec<DetectedFormat> {
+    let mut remaining: Vec<DetectedFormat> = FORMAT_PRECEDENCE
+        .iter()
+        .map(|format| Arc::new(*format) as DetectedFormat)
+        .chain(custom_formats())
+        .collect();
+
+    let mut formats = Vec::with_capacity(remaining.len());
+    for extensions in precedence {
+        let (preferred, others) = remaining.into_iter().partition(|format: &DetectedFormat| {
+            format
+                .file_extensions()
+                .iter()
+                .any(|extension| extensions.contains(extension))
+        });
+        formats.extend::<Vec<_>>(preferred);
+        remaining = others;
+    }
+
+    formats.extend(remaining);
+    formats
+}
+
+/// A format shared by several sources, or several files of one source.
+pub(crate) struct SharedFormat<F: ?Sized>(Arc<F>);
+
+pub(crate) fn shared_format<F>(format: &Arc<F>) -> Box<dyn Format>
+where
+    F: Format + ?Sized + 'static,
+{
+    Box::new(SharedFormat(format.clone()))
+}
+
+impl<F: Format + ?Sized> Format for SharedFormat<F> {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+        self.0.parse(uri, text)
+    }
+
+    fn parse_value(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        self.0.parse_value(uri, text)
+    }
+}
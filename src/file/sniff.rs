@@ -0,0 +1,115 @@
+use crate::file::registry::DetectedFormat;
+
+/// Number of lines at the start of a file searched for a modeline.
+const MODELINE_LINES: usize = 5;
+
+/// Detects the format of `text` among `formats`, given in order of precedence.
+///
+/// A format named by a shebang or modeline is used as is, as in `#!/usr/bin/env yaml`,
+/// `# -*- mode: toml -*-` or `# vim: set ft=json:`. Otherwise the formats are tried in turn,
+/// those suggested by the first characters of the text first, and the first one parsing the
+/// text is used.
+pub(crate) fn detect_format(text: &str, formats: &[DetectedFormat]) -> Option<DetectedFormat> {
+    if let Some(name) = declared_format(text) {
+        let declared = formats.iter().find(|format| {
+            format
+                .file_extensions()
+                .iter()
+                .any(|ext| ext.eq_ignore_ascii_case(&name))
+        });
+
+        if let Some(format) = declared {
+            return Some(format.clone());
+        }
+    }
+
+    let suggested = suggested_extensions(text);
+    let suggested_formats = formats.iter().filter(|format| {
+        format
+            .file_extensions()
+            .iter()
+            .any(|ext| suggested.contains(ext))
+    });
+    let other_formats = formats.iter().filter(|format| {
+        !format
+            .file_extensions()
+            .iter()
+            .any(|ext| suggested.contains(ext))
+    });
+
+    suggested_formats
+        .chain(other_formats)
+        .find(|format| format.parse(None, text).is_ok())
+        .cloned()
+}
+
+/// Returns the name of the format declared by a shebang or a modeline.
+fn declared_format(text: &str) -> Option<String> {
+    if let Some(interpreter) = text.strip_prefix("#!") {
+        let interpreter = interpreter.lines().next().unwrap_or_default();
+        let program = interpreter.split_whitespace().last()?;
+        let name = program.rsplit('/').next().unwrap_or(program);
+        return Some(name.to_owned());
+    }
+
+    text.lines().take(MODELINE_LINES).find_map(modeline_format)
+}
+
+/// Returns the format named by an Emacs or Vim modeline on `line`.
+fn modeline_format(line: &str) -> Option<String> {
+    // Emacs: `-*- mode: yaml -*-` or `-*- yaml -*-`
+    if let Some(start) = line.find("-*-") {
+        let rest = &line[start + 3..];
+        let end = rest.find("-*-")?;
+        let settings = rest[..end].trim();
+
+        let mode = settings
+            .split(';')
+            .filter_map(|setting| {
+                let colon = setting.find(':')?;
+                let (key, value) = (&setting[..colon], &setting[colon + 1..]);
+                if key.trim().eq_ignore_ascii_case("mode") {
+                    Some(value.trim())
+                } else {
+                    None
+                }
+            })
+            .next()
+            .unwrap_or(settings);
+
+        return Some(mode.to_owned());
+    }
+
+    // Vim: `vim: set ft=yaml:` or `vi: filetype=yaml`
+    let start = ["vim:", "vi:", "ex:"]
+        .iter()
+        .filter_map(|marker| line.find(marker).map(|index| index + marker.len()))
+        .next()?;
+
+    line[start..]
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .filter_map(|setting| {
+            let equals = setting.find('=')?;
+            let (key, value) = (&setting[..equals], &setting[equals + 1..]);
+            match key {
+                "ft" | "filetype" | "syntax" | "syn" => Some(value.to_owned()),
+                _ => None,
+            }
+        })
+        .next()
+}
+
+/// Returns the extensions of the formats a text most likely is, given its first characters.
+fn suggested_extensions(text: &str) -> &'static [&'static str] {
+    let text = text.trim_start();
+
+    if text.starts_with("---") {
+        &["yaml"]
+    } else if text.starts_with('{') {
+        &["json", "jsonc", "json5"]
+    } else if text.starts_with('(') {
+        &["ron"]
+    } else {
+        &[]
+    }
+}
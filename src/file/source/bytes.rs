@@ -0,0 +1,81 @@
+use std::error::Error;
+use std::io;
+
+use crate::{
+    file::compression::decompress,
+    file::source::{resolve_text, FileSourceResult},
+    file::{FileSource, FileStoredFormat},
+    Format,
+};
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+const UTF16LE_BOM: &[u8] = &[0xff, 0xfe];
+const UTF16BE_BOM: &[u8] = &[0xfe, 0xff];
+
+/// Describes a file sourced from bytes in memory, such as a file embedded in the executable.
+///
+/// The bytes are UTF-8 text, or UTF-16 text starting with a byte order mark. A UTF-8 byte
+/// order mark is ignored. Bytes starting with the magic bytes of gzip or zstd are decompressed
+/// first, with the `gzip` or `zstd` feature.
+#[derive(Clone, Debug)]
+pub struct FileSourceBytes {
+    /// Label of the bytes in errors
+    uri: String,
+
+    bytes: Vec<u8>,
+}
+
+impl FileSourceBytes {
+    pub fn new<B: Into<Vec<u8>>>(uri: &str, bytes: B) -> Self {
+        Self {
+            uri: uri.into(),
+            bytes: bytes.into(),
+        }
+    }
+}
+
+impl<F> FileSource<F> for FileSourceBytes
+where
+    F: Format + FileStoredFormat + 'static,
+{
+    fn resolve(
+        &self,
+        format_hint: Option<F>,
+    ) -> Result<FileSourceResult, Box<dyn Error + Send + Sync>> {
+        let bytes = decompress(&self.uri, self.bytes.clone())?;
+        let content = decode_text(&self.uri, &bytes)?;
+        resolve_text(Some(self.uri.clone()), content, format_hint)
+    }
+}
+
+/// Decodes UTF-8 text, or UTF-16 text starting with a byte order mark, labelled `uri` in errors.
+pub(crate) fn decode_text(uri: &str, bytes: &[u8]) -> Result<String, io::Error> {
+    let decoded = if bytes.starts_with(UTF8_BOM) {
+        String::from_utf8(bytes[UTF8_BOM.len()..].to_vec()).map_err(|err| err.to_string())
+    } else if bytes.starts_with(UTF16LE_BOM) {
+        decode_utf16(&bytes[UTF16LE_BOM.len()..], u16::from_le_bytes)
+    } else if bytes.starts_with(UTF16BE_BOM) {
+        decode_utf16(&bytes[UTF16BE_BOM.len()..], u16::from_be_bytes)
+    } else {
+        String::from_utf8(bytes.to_vec()).map_err(|err| err.to_string())
+    };
+
+    decoded.map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("configuration file \"{}\" is not valid text: {}", uri, err),
+        )
+    })
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Result<String, String> {
+    if bytes.len() % 2 != 0 {
+        return Err("UTF-16 text has an odd number of bytes".into());
+    }
+
+    let units: Vec<u16> = bytes
+        .chunks(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).map_err(|err| err.to_string())
+}
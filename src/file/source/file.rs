@@ -1,99 +1,367 @@
 use std::env;
 use std::error::Error;
+use std::fmt;
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use crate::file::{
-    format::ALL_EXTENSIONS, source::FileSourceResult, FileSource, FileStoredFormat, Format,
-};
+use crate::file::compression::{compressed_paths, decompress, format_extension};
+use crate::file::registry::{detectable_formats, shared_format, DetectedFormat};
+use crate::file::sniff::detect_format;
+use crate::file::{source::FileSourceResult, FileSource, FileStoredFormat, Format};
 
 /// Describes a file sourced from a file
 #[derive(Clone, Debug)]
 pub struct FileSourceFile {
     /// Path of configuration file
     name: PathBuf,
+
+    /// Directories searched for a relative path, in order of precedence
+    search_paths: Vec<PathBuf>,
+
+    /// What to do with the file found in several search paths
+    search_policy: SearchPolicy,
+
+    /// Extensions of the formats to detect before the others, in order of precedence
+    format_precedence: Vec<&'static [&'static str]>,
+
+    /// Whether several files found for one base name is an error, rather than resolved by
+    /// precedence
+    error_on_ambiguity: bool,
+
+    /// Whether the format of a file without a registered extension is detected from its content
+    detect_format: bool,
+}
+
+/// How a file found in several of the [search paths](crate::File::search_path) is used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchPolicy {
+    /// Only the file found in the first search path is used.
+    #[default]
+    FirstMatch,
+
+    /// Every file found is merged, those found in earlier search paths taking precedence.
+    MergeAll,
 }
 
 impl FileSourceFile {
     pub fn new(name: PathBuf) -> Self {
-        Self { name }
+        Self {
+            name,
+            search_paths: Vec::new(),
+            search_policy: SearchPolicy::default(),
+            format_precedence: Vec::new(),
+            error_on_ambiguity: false,
+            detect_format: false,
+        }
+    }
+
+    pub(crate) fn add_search_path(&mut self, path: PathBuf) {
+        self.search_paths.push(path);
+    }
+
+    pub(crate) fn set_search_policy(&mut self, policy: SearchPolicy) {
+        self.search_policy = policy;
+    }
+
+    pub(crate) fn set_format_precedence(&mut self, formats: &[&dyn FileStoredFormat]) {
+        self.format_precedence = formats
+            .iter()
+            .map(|format| format.file_extensions())
+            .collect();
+    }
+
+    pub(crate) fn set_error_on_ambiguity(&mut self, error: bool) {
+        self.error_on_ambiguity = error;
+    }
+
+    pub(crate) fn set_detect_format(&mut self, detect: bool) {
+        self.detect_format = detect;
+    }
+
+    /// Returns the paths the file is looked for at, before any extension is added.
+    ///
+    /// A relative path is looked for in each search path, or in the current directory when
+    /// there are none.
+    pub fn candidates(&self) -> Vec<PathBuf> {
+        if self.name.is_absolute() {
+            return vec![self.name.clone()];
+        }
+
+        let current_dir = env::current_dir().unwrap_or_default();
+        if self.search_paths.is_empty() {
+            return vec![current_dir.join(&self.name)];
+        }
+
+        self.search_paths
+            .iter()
+            .map(|dir| current_dir.join(dir).join(&self.name))
+            .collect()
     }
 
-    fn find_file<F>(
+    fn find_files<F>(
         &self,
         format_hint: Option<F>,
-    ) -> Result<(PathBuf, Box<dyn Format>), Box<dyn Error + Send + Sync>>
+    ) -> Result<Vec<FoundMatch>, Box<dyn Error + Send + Sync>>
     where
         F: FileStoredFormat + Format + 'static,
     {
-        let filename = if self.name.is_absolute() {
-            self.name.clone()
-        } else {
-            env::current_dir()?.as_path().join(&self.name)
-        };
+        let format_hint = format_hint.map(Arc::new);
+        let formats = detectable_formats(&self.format_precedence);
+        let mut probed = Vec::new();
+        let mut found = Vec::new();
+
+        for filename in self.candidates() {
+            let matches = find_file(
+                filename,
+                format_hint.as_ref(),
+                &formats,
+                self.detect_format,
+                &mut probed,
+            )?;
 
-        // First check for an _exact_ match
-        if filename.is_file() {
-            return if let Some(format) = format_hint {
-                Ok((filename, Box::new(format)))
-            } else {
-                for (format, extensions) in ALL_EXTENSIONS.iter() {
-                    if extensions.contains(
-                        &filename
-                            .extension()
-                            .unwrap_or_default()
-                            .to_string_lossy()
-                            .as_ref(),
-                    ) {
-                        return Ok((filename, Box::new(*format)));
-                    }
+            if matches.len() > 1 && self.error_on_ambiguity {
+                return Err(Box::new(FileLookupError::Ambiguous {
+                    name: self.name.clone(),
+                    matches: matches.into_iter().map(|(path, _)| path).collect(),
+                }));
+            }
+
+            // The first file found takes precedence, the others are kept to be reported
+            let mut matches = matches.into_iter();
+            if let Some(file) = matches.next() {
+                found.push((file, matches.map(|(path, _)| path).collect()));
+
+                if self.search_policy == SearchPolicy::FirstMatch {
+                    break;
                 }
+            }
+        }
 
-                Err(Box::new(io::Error::new(
-                    io::ErrorKind::NotFound,
-                    format!(
-                        "configuration file \"{}\" is not of a registered file format",
-                        filename.to_string_lossy()
-                    ),
-                )))
-            };
+        if found.is_empty() {
+            return Err(Box::new(FileLookupError::NotFound {
+                name: self.name.clone(),
+                probed,
+                extensions: formats
+                    .iter()
+                    .flat_map(|format| format.file_extensions())
+                    .copied()
+                    .collect(),
+            }));
         }
-        // Adding a dummy extension will make sure we will not override secondary extensions, i.e. "file.local"
-        // This will make the following set_extension function calls to append the extension.
-        let mut filename = add_dummy_extension(filename);
 
-        match format_hint {
-            Some(format) => {
-                for ext in format.file_extensions() {
-                    filename.set_extension(ext);
+        Ok(found)
+    }
+
+    fn read_file(
+        &self,
+        (filename, format): FoundFile,
+        ambiguous_matches: Vec<PathBuf>,
+    ) -> Result<FileSourceResult, Box<dyn Error + Send + Sync>> {
+        // Attempt to use a relative path for the URI
+        let uri = env::current_dir()
+            .ok()
+            .and_then(|base| pathdiff::diff_paths(&filename, base))
+            .unwrap_or_else(|| filename.to_path_buf());
+
+        // Read contents from file, decompressing it if need be
+        let bytes = decompress(&uri.to_string_lossy(), fs::read(&filename)?)?;
+        let text = String::from_utf8(bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        let (format, detected_format) = match format {
+            Some(format) => (format, None),
+            None => match detect_format(&text, &detectable_formats(&self.format_precedence)) {
+                Some(format) => (shared_format(&format), Some(format)),
+                None => {
+                    return Err(Box::new(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "configuration file \"{}\" is not of a registered file format, \
+                             nor could one be detected from its content",
+                            filename.to_string_lossy()
+                        ),
+                    )))
+                }
+            },
+        };
+
+        Ok(FileSourceResult {
+            uri: Some(uri.to_string_lossy().into_owned()),
+            content: text,
+            format,
+            detected_format,
+            ambiguous_matches,
+        })
+    }
+}
 
-                    if filename.is_file() {
-                        return Ok((filename, Box::new(format)));
-                    }
+/// The path of a file found and the format to parse it with, unless it is to be detected from
+/// its content.
+type FoundFile = (PathBuf, Option<Box<dyn Format>>);
+
+/// The file found for a candidate path, and the other files found with the same base name that
+/// it took precedence over.
+type FoundMatch = (FoundFile, Vec<PathBuf>);
+
+/// Error of a file looked up by name which does not resolve to exactly one file.
+///
+/// It is returned as [`ConfigError::Foreign`](crate::ConfigError::Foreign), from which it can
+/// be downcast.
+#[derive(Debug)]
+pub enum FileLookupError {
+    /// No file was found.
+    NotFound {
+        /// The name the file was looked up by.
+        name: PathBuf,
+
+        /// Every path probed, in order, with the extensions of the formats added.
+        probed: Vec<PathBuf>,
+
+        /// The extensions of the formats registered for auto-detection, in order of precedence.
+        extensions: Vec<&'static str>,
+    },
+
+    /// Several files were found with the same base name and different extensions, when
+    /// [`File::error_on_ambiguity`](crate::File::error_on_ambiguity) is set.
+    Ambiguous {
+        /// The name the file was looked up by.
+        name: PathBuf,
+
+        /// The files found.
+        matches: Vec<PathBuf>,
+    },
+}
+
+impl fmt::Display for FileLookupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound {
+                name,
+                probed,
+                extensions,
+            } => write!(
+                f,
+                "configuration file \"{}\" not found, tried {} (registered extensions: {})",
+                name.to_string_lossy(),
+                quoted_paths(probed),
+                extensions.join(", ")
+            ),
+
+            Self::Ambiguous { name, matches } => write!(
+                f,
+                "configuration file \"{}\" is ambiguous, found {}",
+                name.to_string_lossy(),
+                quoted_paths(matches)
+            ),
+        }
+    }
+}
+
+impl Error for FileLookupError {}
+
+fn quoted_paths(paths: &[PathBuf]) -> String {
+    let quoted: Vec<String> = paths
+        .iter()
+        .map(|path| format!("\"{}\"", path.to_string_lossy()))
+        .collect();
+    quoted.join(", ")
+}
+
+/// Finds the file at `filename`, or with the extension of one of `formats` added, recording
+/// the paths probed.
+///
+/// Every file found with an added extension is returned in order of precedence, so that
+/// ambiguities can be reported.
+fn find_file<F>(
+    filename: PathBuf,
+    format_hint: Option<&Arc<F>>,
+    formats: &[DetectedFormat],
+    detect_format: bool,
+    probed: &mut Vec<PathBuf>,
+) -> Result<Vec<FoundFile>, Box<dyn Error + Send + Sync>>
+where
+    F: FileStoredFormat + Format + 'static,
+{
+    // First check for an _exact_ match, or the same file compressed
+    let mut exact = None;
+    for path in std::iter::once(filename.clone()).chain(compressed_paths(&filename)) {
+        probed.push(path.clone());
+
+        if path.is_file() {
+            exact = Some(path);
+            break;
+        }
+    }
+
+    if let Some(filename) = exact {
+        return if let Some(format) = format_hint {
+            Ok(vec![(filename, Some(shared_format(format)))])
+        } else {
+            // The extension before that of the compression tells the format
+            let ext = format_extension(&filename).unwrap_or_default();
+            for format in formats {
+                if format.file_extensions().contains(&ext.as_str()) {
+                    return Ok(vec![(filename, Some(shared_format(format)))]);
                 }
             }
 
-            None => {
-                for format in ALL_EXTENSIONS.keys() {
-                    for ext in format.extensions() {
-                        filename.set_extension(ext);
+            if detect_format {
+                return Ok(vec![(filename, None)]);
+            }
 
-                        if filename.is_file() {
-                            return Ok((filename, Box::new(*format)));
-                        }
-                    }
+            Err(Box::new(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "configuration file \"{}\" is not of a registered file format",
+                    filename.to_string_lossy()
+                ),
+            )))
+        };
+    }
+    // Adding a dummy extension will make sure we will not override secondary extensions, i.e. "file.local"
+    // This will make the following set_extension function calls to append the extension.
+    let mut filename = add_dummy_extension(filename);
+    let mut found: Vec<FoundFile> = Vec::new();
+
+    match format_hint {
+        Some(format) => {
+            for ext in format.file_extensions() {
+                filename.set_extension(ext);
+                find_with_extension(&filename, format, &mut found, probed);
+            }
+        }
+
+        None => {
+            for format in formats {
+                for ext in format.file_extensions() {
+                    filename.set_extension(ext);
+                    find_with_extension(&filename, format, &mut found, probed);
                 }
             }
         }
+    }
 
-        Err(Box::new(io::Error::new(
-            io::ErrorKind::NotFound,
-            format!(
-                "configuration file \"{}\" not found",
-                self.name.to_string_lossy()
-            ),
-        )))
+    Ok(found)
+}
+
+/// Finds the file at `filename`, with the extension of `format`, and then the same file
+/// compressed.
+fn find_with_extension<F>(
+    filename: &Path,
+    format: &Arc<F>,
+    found: &mut Vec<FoundFile>,
+    probed: &mut Vec<PathBuf>,
+) where
+    F: Format + ?Sized + 'static,
+{
+    for path in std::iter::once(filename.to_path_buf()).chain(compressed_paths(filename)) {
+        probed.push(path.clone());
+
+        if path.is_file() {
+            found.push((path, Some(shared_format(format))));
+        }
     }
 }
 
@@ -106,22 +374,25 @@ where
         format_hint: Option<F>,
     ) -> Result<FileSourceResult, Box<dyn Error + Send + Sync>> {
         // Find file
-        let (filename, format) = self.find_file(format_hint)?;
+        let (file, ambiguous_matches) = self
+            .find_files(format_hint)?
+            .into_iter()
+            .next()
+            .expect("a file was found");
 
-        // Attempt to use a relative path for the URI
-        let uri = env::current_dir()
-            .ok()
-            .and_then(|base| pathdiff::diff_paths(&filename, base))
-            .unwrap_or_else(|| filename.clone());
-
-        // Read contents from file
-        let text = fs::read_to_string(filename)?;
+        self.read_file(file, ambiguous_matches)
+    }
 
-        Ok(FileSourceResult {
-            uri: Some(uri.to_string_lossy().into_owned()),
-            content: text,
-            format,
-        })
+    fn resolve_all(
+        &self,
+        format_hint: Option<F>,
+    ) -> Result<Vec<FileSourceResult>, Box<dyn Error + Send + Sync>> {
+        // Files of lower precedence come first, to be merged over
+        self.find_files(format_hint)?
+            .into_iter()
+            .rev()
+            .map(|(file, ambiguous_matches)| self.read_file(file, ambiguous_matches))
+            .collect()
     }
 }
 
@@ -1,9 +1,15 @@
+pub mod bytes;
 pub mod file;
+pub mod reader;
 pub mod string;
 
 use std::error::Error;
 use std::fmt::Debug;
+use std::io;
+use std::path::PathBuf;
 
+use crate::file::registry::{detectable_formats, shared_format, DetectedFormat};
+use crate::file::sniff::detect_format;
 use crate::{file::FileStoredFormat, Format};
 
 /// Describes where the file is sourced
@@ -15,12 +21,24 @@ where
         &self,
         format_hint: Option<T>,
     ) -> Result<FileSourceResult, Box<dyn Error + Send + Sync>>;
+
+    /// Resolves every file to merge, those of lower precedence first.
+    ///
+    /// By default this is only the file given by [`resolve`](Self::resolve).
+    fn resolve_all(
+        &self,
+        format_hint: Option<T>,
+    ) -> Result<Vec<FileSourceResult>, Box<dyn Error + Send + Sync>> {
+        self.resolve(format_hint).map(|result| vec![result])
+    }
 }
 
 pub struct FileSourceResult {
     pub(crate) uri: Option<String>,
     pub(crate) content: String,
     pub(crate) format: Box<dyn Format>,
+    pub(crate) detected_format: Option<DetectedFormat>,
+    pub(crate) ambiguous_matches: Vec<PathBuf>,
 }
 
 impl FileSourceResult {
@@ -35,4 +53,66 @@ impl FileSourceResult {
     pub fn format(&self) -> &dyn Format {
         self.format.as_ref()
     }
+
+    /// Returns the format detected from the content, when it could not be told from the file
+    /// name.
+    ///
+    /// A custom format registered without extensions is returned as well, so that every
+    /// successful detection is visible.
+    pub fn detected_format(&self) -> Option<&(dyn FileStoredFormat + Send + Sync)> {
+        self.detected_format.as_deref()
+    }
+
+    /// Returns the other files found with the same base name and a different extension, which
+    /// the file read took precedence over.
+    ///
+    /// They are only an error with [`File::error_on_ambiguity`](crate::File::error_on_ambiguity).
+    pub fn ambiguous_matches(&self) -> &[PathBuf] {
+        &self.ambiguous_matches
+    }
+}
+
+/// Resolves text held in memory, parsed with the format given or else one detected from it.
+pub(crate) fn resolve_text<F>(
+    uri: Option<String>,
+    content: String,
+    format_hint: Option<F>,
+) -> Result<FileSourceResult, Box<dyn Error + Send + Sync>>
+where
+    F: Format + 'static,
+{
+    if let Some(format) = format_hint {
+        return Ok(FileSourceResult {
+            uri,
+            content,
+            format: Box::new(format),
+            detected_format: None,
+            ambiguous_matches: Vec::new(),
+        });
+    }
+
+    match detect_format(&content, &detectable_formats(&[])) {
+        Some(format) => Ok(FileSourceResult {
+            uri,
+            content,
+            format: shared_format(&format),
+            detected_format: Some(format),
+            ambiguous_matches: Vec::new(),
+        }),
+
+        None => {
+            let source = match uri {
+                Some(uri) => format!("configuration file \"{}\"", uri),
+                None => "configuration string".to_owned(),
+            };
+
+            Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} has no format set, nor could one be detected from its content",
+                    source
+                ),
+            )))
+        }
+    }
 }
@@ -0,0 +1,97 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    file::compression::decompress,
+    file::source::bytes::decode_text,
+    file::source::{resolve_text, FileSourceResult},
+    file::{FileSource, FileStoredFormat},
+    Format,
+};
+
+/// Describes a file sourced from a reader, such as the standard input.
+///
+/// The reader is read to its end the first time the source is resolved, and what was read is
+/// used from then on, also by the clones of the source. The text is decoded as by
+/// [`FileSourceBytes`](super::bytes::FileSourceBytes), after decompressing it the same way.
+#[derive(Clone)]
+pub struct FileSourceReader {
+    /// Label of the reader in errors
+    uri: String,
+
+    content: Arc<Mutex<ReaderContent>>,
+}
+
+enum ReaderContent {
+    Unread(Box<dyn Read + Send>),
+    Read(Vec<u8>),
+    Failed(io::ErrorKind, String),
+}
+
+impl FileSourceReader {
+    pub fn new<R: Read + Send + 'static>(uri: &str, reader: R) -> Self {
+        Self {
+            uri: uri.into(),
+            content: Arc::new(Mutex::new(ReaderContent::Unread(Box::new(reader)))),
+        }
+    }
+
+    /// Reads the standard input, labelled `-` in errors.
+    pub fn stdin() -> Self {
+        Self::new("-", io::stdin())
+    }
+
+    fn read(&self) -> io::Result<Vec<u8>> {
+        let mut content = self.content.lock().unwrap_or_else(|err| err.into_inner());
+
+        if let ReaderContent::Unread(_) = *content {
+            let mut reader = match mem::replace(&mut *content, ReaderContent::Read(Vec::new())) {
+                ReaderContent::Unread(reader) => reader,
+                _ => unreachable!(),
+            };
+
+            let mut bytes = Vec::new();
+            *content = match reader.read_to_end(&mut bytes) {
+                Ok(_) => ReaderContent::Read(bytes),
+                Err(err) => ReaderContent::Failed(
+                    err.kind(),
+                    format!(
+                        "could not read configuration file \"{}\": {}",
+                        self.uri, err
+                    ),
+                ),
+            };
+        }
+
+        match &*content {
+            ReaderContent::Read(bytes) => Ok(bytes.clone()),
+            ReaderContent::Failed(kind, message) => Err(io::Error::new(*kind, message.clone())),
+            ReaderContent::Unread(_) => unreachable!(),
+        }
+    }
+}
+
+impl fmt::Debug for FileSourceReader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FileSourceReader")
+            .field("uri", &self.uri)
+            .finish()
+    }
+}
+
+impl<F> FileSource<F> for FileSourceReader
+where
+    F: Format + FileStoredFormat + 'static,
+{
+    fn resolve(
+        &self,
+        format_hint: Option<F>,
+    ) -> Result<FileSourceResult, Box<dyn Error + Send + Sync>> {
+        let bytes = decompress(&self.uri, self.read()?)?;
+        let content = decode_text(&self.uri, &bytes)?;
+        resolve_text(Some(self.uri.clone()), content, format_hint)
+    }
+}
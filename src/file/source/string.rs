@@ -1,7 +1,7 @@
 use std::error::Error;
 
 use crate::{
-    file::source::FileSourceResult,
+    file::source::{resolve_text, FileSourceResult},
     file::{FileSource, FileStoredFormat},
     Format,
 };
@@ -24,10 +24,6 @@ where
         &self,
         format_hint: Option<F>,
     ) -> Result<FileSourceResult, Box<dyn Error + Send + Sync>> {
-        Ok(FileSourceResult {
-            uri: None,
-            content: self.0.clone(),
-            format: Box::new(format_hint.expect("from_str requires a set file format")),
-        })
+        resolve_text(None, self.0.clone(), format_hint)
     }
 }
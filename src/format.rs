@@ -22,6 +22,19 @@ pub trait Format {
         uri: Option<&String>,
         text: &str,
     ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>>;
+
+    /// Parses a single value, such as the text of an environment variable, which unlike a whole
+    /// file may be a scalar or an array.
+    ///
+    /// By default only what [`parse`](Self::parse) accepts is supported, giving a table.
+    fn parse_value(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Value, Box<dyn Error + Send + Sync>> {
+        self.parse(uri, text)
+            .map(|table| Value::new(uri, ValueKind::Table(table)))
+    }
 }
 
 // Have a proper error fire if the root of a file is ever not a Table
@@ -39,7 +52,10 @@ pub fn extract_root_table(
         ValueKind::U64(value) => Err(Unexpected::U64(value)),
         ValueKind::U128(value) => Err(Unexpected::U128(value)),
         ValueKind::Float(value) => Err(Unexpected::Float(value)),
+        ValueKind::Decimal(value) => Err(Unexpected::Decimal(value)),
         ValueKind::String(value) => Err(Unexpected::Str(value)),
+        ValueKind::Bytes(value) => Err(Unexpected::Bytes(value)),
+        ValueKind::Datetime(value) => Err(Unexpected::Datetime(value)),
     }
     .map_err(|err| ConfigError::invalid_root(uri, err))
     .map_err(|err| Box::new(err) as Box<dyn Error + Send + Sync>)
@@ -4,9 +4,11 @@
 //! configuration from a variety of sources:
 //!
 //!  - Environment variables
+//!  - Directories with one file per key, such as mounted secrets
 //!  - String literals in well-known formats
 //!  - Another Config instance
-//!  - Files: TOML, JSON, YAML, INI, RON, JSON5 and custom ones defined with Format trait
+//!  - Files embedded in the executable, parsed when it is built
+//!  - Files: TOML, JSON (optionally with comments), YAML, INI, RON, JSON5, KDL and custom ones defined with Format trait
 //!  - Manual, programmatic override (via a `.set` method on the Config instance)
 //!
 //! Additionally, Config supports:
@@ -21,8 +23,12 @@
 // #![warn(missing_docs)]
 
 pub mod builder;
+mod bytes;
 mod config;
+mod datetime;
 mod de;
+mod directory;
+mod embedded;
 mod env;
 mod error;
 mod file;
@@ -31,19 +37,32 @@ mod map;
 mod path;
 mod ser;
 mod source;
+mod units;
 mod value;
 
 pub use crate::builder::ConfigBuilder;
 pub use crate::config::Config;
-pub use crate::env::Environment;
+pub use crate::datetime::{Date, Datetime, DatetimeParseError, Offset, Time};
+pub use crate::directory::Directory;
+pub use crate::embedded::{embed, Embedded};
+pub use crate::env::{Environment, TypeHint};
 pub use crate::error::ConfigError;
-pub use crate::file::source::FileSource;
-pub use crate::file::{File, FileFormat, FileSourceFile, FileSourceString, FileStoredFormat};
+pub use crate::file::source::{FileSource, FileSourceResult};
+#[cfg(feature = "ini")]
+pub use crate::file::IniFormat;
+pub use crate::file::{
+    register_format, unregister_format, File, FileFormat, FileLookupError, FileSourceBytes,
+    FileSourceFile, FileSourceReader, FileSourceString, FileStoredFormat, RegisteredFormat,
+    SearchPolicy,
+};
+#[cfg(feature = "yaml")]
+pub use crate::file::{YamlDocuments, YamlFormat};
 pub use crate::format::Format;
 pub use crate::map::Map;
 #[cfg(feature = "async")]
 pub use crate::source::AsyncSource;
 pub use crate::source::Source;
+pub use crate::units::ByteSize;
 pub use crate::value::{Value, ValueKind};
 
 #[allow(deprecated)]
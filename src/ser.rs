@@ -1,12 +1,17 @@
+use std::convert::TryFrom;
 use std::fmt::Display;
 use std::fmt::Write as _;
 
 use serde::ser;
 
+use crate::datetime::{Datetime, DatetimeParseError, DATETIME_NAME};
 use crate::error::{ConfigError, Result};
 use crate::value::{Value, ValueKind};
 use crate::Config;
 
+/// Name of the field serde_json serializes the text of an arbitrary precision number as.
+const JSON_NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
 #[derive(Default, Debug)]
 pub struct ConfigSerializer {
     keys: Vec<SerKey>,
@@ -125,18 +130,22 @@ impl<'a> ser::Serializer for &'a mut ConfigSerializer {
         self.serialize_u64(v.into())
     }
 
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok> {
+        self.serialize_primitive(v)
+    }
+
     fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
-        if v > (i64::max_value() as u64) {
-            Err(ConfigError::Message(format!(
-                "value {} is greater than the max {}",
-                v,
-                i64::max_value()
-            )))
-        } else {
-            self.serialize_i64(v as i64)
+        // Values beyond `i64` are kept unsigned rather than rejected
+        match i64::try_from(v) {
+            Ok(v) => self.serialize_i64(v),
+            Err(_) => self.serialize_primitive(v),
         }
     }
 
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok> {
+        self.serialize_primitive(v)
+    }
+
     fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
         self.serialize_f64(v.into())
     }
@@ -154,13 +163,7 @@ impl<'a> ser::Serializer for &'a mut ConfigSerializer {
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
-        use serde::ser::SerializeSeq;
-        let mut seq = self.serialize_seq(Some(v.len()))?;
-        for byte in v {
-            seq.serialize_element(byte)?;
-        }
-        seq.end();
-        Ok(())
+        self.serialize_primitive(ValueKind::Bytes(v.to_vec()))
     }
 
     fn serialize_none(self) -> Result<Self::Ok> {
@@ -191,10 +194,19 @@ impl<'a> ser::Serializer for &'a mut ConfigSerializer {
         self.serialize_str(variant)
     }
 
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Self::Ok>
     where
         T: ?Sized + ser::Serialize,
     {
+        // Keep datetimes as such rather than the string they serialize to
+        if name == DATETIME_NAME {
+            let datetime = value.serialize(StringKeySerializer)?;
+            let datetime: Datetime = datetime
+                .parse()
+                .map_err(|err: DatetimeParseError| ConfigError::Message(err.to_string()))?;
+            return self.serialize_primitive(datetime);
+        }
+
         value.serialize(self)
     }
 
@@ -351,7 +363,7 @@ impl<'a> ser::SerializeTupleVariant for SeqSerializer<'a> {
     }
 }
 
-impl<'a> ser::SerializeMap for &'a mut ConfigSerializer {
+impl ser::SerializeMap for &mut ConfigSerializer {
     type Ok = ();
     type Error = ConfigError;
 
@@ -379,7 +391,7 @@ impl<'a> ser::SerializeMap for &'a mut ConfigSerializer {
     }
 }
 
-impl<'a> ser::SerializeStruct for &'a mut ConfigSerializer {
+impl ser::SerializeStruct for &mut ConfigSerializer {
     type Ok = ();
     type Error = ConfigError;
 
@@ -387,6 +399,14 @@ impl<'a> ser::SerializeStruct for &'a mut ConfigSerializer {
     where
         T: ?Sized + ser::Serialize,
     {
+        // serde_json with arbitrary precision serializes a number as a struct holding its text
+        if key == JSON_NUMBER_TOKEN {
+            let number = value.serialize(StringKeySerializer)?;
+            let number = ValueKind::parse_number(&number)
+                .ok_or_else(|| ConfigError::Message(format!("invalid number `{}`", number)))?;
+            return self.serialize_primitive(number);
+        }
+
         self.push_key(key);
         value.serialize(&mut **self)?;
         self.pop_key();
@@ -398,7 +418,7 @@ impl<'a> ser::SerializeStruct for &'a mut ConfigSerializer {
     }
 }
 
-impl<'a> ser::SerializeStructVariant for &'a mut ConfigSerializer {
+impl ser::SerializeStructVariant for &mut ConfigSerializer {
     type Ok = ();
     type Error = ConfigError;
 
@@ -27,7 +27,20 @@ pub trait Source: Debug {
     }
 }
 
-fn set_value(cache: &mut Value, key: &str, value: &Value) {
+/// Layers each source onto the cache itself, so keys such as `servers[1].port` update an array
+/// from an earlier source instead of replacing it.
+fn collect_each_to<'a, I>(sources: I, cache: &mut Value) -> Result<()>
+where
+    I: IntoIterator<Item = &'a (dyn Source + Send + Sync)>,
+{
+    for source in sources {
+        source.collect_to(cache)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn set_value(cache: &mut Value, key: &str, value: &Value) {
     match path::Expression::from_str(key) {
         // Set using the path
         Ok(expr) => expr.set(cache, value.clone()),
@@ -102,6 +115,10 @@ impl Source for Vec<Box<dyn Source + Send + Sync>> {
             unreachable!();
         }
     }
+
+    fn collect_to(&self, cache: &mut Value) -> Result<()> {
+        collect_each_to(self.iter().map(|source| &**source), cache)
+    }
 }
 
 impl Source for [Box<dyn Source + Send + Sync>] {
@@ -122,6 +139,10 @@ impl Source for [Box<dyn Source + Send + Sync>] {
             unreachable!();
         }
     }
+
+    fn collect_to(&self, cache: &mut Value) -> Result<()> {
+        collect_each_to(self.iter().map(|source| &**source), cache)
+    }
 }
 
 impl<T> Source for Vec<T>
@@ -145,4 +166,12 @@ where
             unreachable!();
         }
     }
+
+    fn collect_to(&self, cache: &mut Value) -> Result<()> {
+        collect_each_to(
+            self.iter()
+                .map(|source| source as &(dyn Source + Send + Sync)),
+            cache,
+        )
+    }
 }
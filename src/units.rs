@@ -0,0 +1,238 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+
+use serde::de;
+use serde::ser;
+
+/// Name of the newtype a [`ByteSize`] deserializes as, letting the configuration deserializer
+/// parse strings such as `"512MiB"` itself and report failures as type errors.
+pub(crate) const BYTE_SIZE_NAME: &str = "$__config_private_ByteSize";
+
+/// Name and fields of the struct `std::time::Duration` deserializes as.
+pub(crate) const DURATION_NAME: &str = "Duration";
+pub(crate) const DURATION_FIELDS: &[&str] = &["secs", "nanos"];
+
+/// A number of bytes, read from an integer or a string with a unit such as `"512MiB"`.
+///
+/// Decimal (`kB`, `MB`, `GB`, `TB`, `PB`) and binary (`KiB`, `MiB`, `GiB`, `TiB`, `PiB`) units
+/// are understood, ignoring case, as well as `B` for bytes.
+///
+/// ```rust
+/// # use config::{ByteSize, Config};
+/// let config = Config::builder()
+///     .set_override("cache", "1.5 GiB")?
+///     .build()?;
+///
+/// assert_eq!(config.get::<ByteSize>("cache")?, ByteSize(3 * 512 * 1024 * 1024));
+/// # Ok::<(), config::ConfigError>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// Returns the number of bytes.
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// Writes the size with the largest unit that represents it exactly, e.g. `512MiB`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = BYTE_UNITS
+            .iter()
+            .filter(|&&(_, factor)| factor > 1 && self.0 != 0 && self.0 % factor == 0)
+            .max_by_key(|&&(_, factor)| factor);
+
+        match unit {
+            Some(&(unit, factor)) => write!(f, "{}{}", self.0 / factor, unit),
+            None => write!(f, "{}B", self.0),
+        }
+    }
+}
+
+impl ser::Serialize for ByteSize {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for ByteSize {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct ByteSizeVisitor;
+
+        impl<'de> de::Visitor<'de> for ByteSizeVisitor {
+            type Value = ByteSize;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a byte size")
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<ByteSize, E> {
+                Ok(ByteSize(value))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<ByteSize, E>
+            where
+                E: de::Error,
+            {
+                u64::try_from(value)
+                    .map(ByteSize)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<ByteSize, E>
+            where
+                E: de::Error,
+            {
+                parse_byte_size(value)
+                    .map(ByteSize)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<ByteSize, D::Error>
+            where
+                D: de::Deserializer<'de>,
+            {
+                deserializer.deserialize_any(self)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(BYTE_SIZE_NAME, ByteSizeVisitor)
+    }
+}
+
+const BYTE_UNITS: &[(&str, u64)] = &[
+    ("B", 1),
+    ("kB", 1_000),
+    ("MB", 1_000_000),
+    ("GB", 1_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("PB", 1_000_000_000_000_000),
+    ("KiB", 1 << 10),
+    ("MiB", 1 << 20),
+    ("GiB", 1 << 30),
+    ("TiB", 1 << 40),
+    ("PiB", 1 << 50),
+];
+
+const DURATION_UNITS: &[(&str, u64)] = &[
+    ("ns", 1),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ms", 1_000_000),
+    ("s", 1_000_000_000),
+    ("m", 60_000_000_000),
+    ("min", 60_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("d", 86_400_000_000_000),
+];
+
+/// Parses a byte size such as `512MiB`, `1.5 GB` or `1024`.
+pub(crate) fn parse_byte_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+
+    let unit = unit.trim_start();
+    let factor = if unit.is_empty() {
+        1
+    } else {
+        BYTE_UNITS
+            .iter()
+            .find(|&&(name, _)| name.eq_ignore_ascii_case(unit))?
+            .1
+    };
+
+    scale(number, factor).and_then(|bytes| u64::try_from(bytes).ok())
+}
+
+/// Parses a duration made of one or more numbers with a unit, such as `1h30m` or `1.5s`.
+///
+/// A number without a unit is a number of seconds.
+pub(crate) fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+
+    if s.bytes().all(|c| c.is_ascii_digit() || c == b'.') {
+        return duration_from_nanos(scale(s, 1_000_000_000)?);
+    }
+
+    let mut nanos: u128 = 0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(split);
+
+        let tail = tail.trim_start();
+        let split = tail
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(split);
+
+        let factor = DURATION_UNITS.iter().find(|&&(name, _)| name == unit)?.1;
+        nanos = nanos.checked_add(scale(number, factor)?)?;
+        rest = tail.trim_start();
+    }
+
+    duration_from_nanos(nanos)
+}
+
+fn duration_from_nanos(nanos: u128) -> Option<Duration> {
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+/// Multiplies a decimal number such as `1.5` by `factor`, truncating any fraction left.
+fn scale(number: &str, factor: u64) -> Option<u128> {
+    let (whole, fraction) = match number.find('.') {
+        Some(dot) => (&number[..dot], &number[dot + 1..]),
+        None => (number, ""),
+    };
+
+    if whole.is_empty() && fraction.is_empty() || fraction.contains('.') {
+        return None;
+    }
+
+    let whole: u128 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let mut scaled = whole.checked_mul(factor.into())?;
+
+    // Digits beyond what fits are too small to matter for any unit
+    let fraction = &fraction[..fraction.len().min(18)];
+    if !fraction.is_empty() {
+        let numerator: u128 = fraction.parse().ok()?;
+        let part = numerator.checked_mul(factor.into())? / 10u128.pow(fraction.len() as u32);
+        scaled = scaled.checked_add(part)?;
+    }
+
+    Some(scaled)
+}
@@ -1,19 +1,24 @@
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::fmt::Display;
+use std::time::Duration;
 
 use serde::de::{Deserialize, Deserializer, Visitor};
 
+use crate::bytes::{decode_prefixed, encode_base64, BASE64_PREFIX};
+use crate::datetime::Datetime;
 use crate::error::{ConfigError, Result, Unexpected};
 use crate::map::Map;
+use crate::units::{parse_byte_size, parse_duration};
 
 /// Underlying kind of the configuration value.
 ///
 /// Standard operations on a `Value` by users of this crate do not require
 /// knowledge of `ValueKind`. Introspection of underlying kind is only required
 /// when the configuration values are unstructured or do not have known types.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum ValueKind {
+    #[default]
     Nil,
     Boolean(bool),
     I64(i64),
@@ -21,7 +26,18 @@ pub enum ValueKind {
     U64(u64),
     U128(u128),
     Float(f64),
+
+    /// A number with a fraction, kept as its original digits so no precision is lost.
+    ///
+    /// Only produced by formats when the `arbitrary_precision` feature is enabled.
+    Decimal(String),
     String(String),
+
+    /// Binary data, such as a certificate or a key.
+    ///
+    /// Strings are decoded into bytes on demand, see [`Value::into_bytes`].
+    Bytes(Vec<u8>),
+    Datetime(Datetime),
     Table(Table),
     Array(Array),
 }
@@ -29,9 +45,51 @@ pub enum ValueKind {
 pub type Array = Vec<Value>;
 pub type Table = Map<String, Value>;
 
-impl Default for ValueKind {
-    fn default() -> Self {
-        Self::Nil
+impl ValueKind {
+    /// Parses the text of a number into the narrowest kind that holds it exactly.
+    ///
+    /// Integers become the first of `I64`, `U64`, `I128` and `U128` they fit, numbers with a
+    /// fraction become `Float`, or `Decimal` with the `arbitrary_precision` feature.
+    pub(crate) fn parse_number(text: &str) -> Option<Self> {
+        if let Ok(value) = text.parse::<i64>() {
+            Some(Self::I64(value))
+        } else if let Ok(value) = text.parse::<u64>() {
+            Some(Self::U64(value))
+        } else if let Ok(value) = text.parse::<i128>() {
+            Some(Self::I128(value))
+        } else if let Ok(value) = text.parse::<u128>() {
+            Some(Self::U128(value))
+        } else if cfg!(feature = "arbitrary_precision") && is_decimal(text) {
+            Some(Self::Decimal(text.to_owned()))
+        } else {
+            text.parse::<f64>().ok().map(Self::Float)
+        }
+    }
+
+    /// Infers a boolean or a number from text, returning `None` for anything else.
+    pub(crate) fn infer(text: &str) -> Option<Self> {
+        // convert to lowercase because bool parsing expects all lowercase
+        match text.to_lowercase().parse::<bool>() {
+            Ok(value) => Some(Self::Boolean(value)),
+            Err(_) => Self::parse_number(text),
+        }
+    }
+}
+
+/// Whether `text` is a plain decimal number such as `-12.50`, without an exponent.
+fn is_decimal(text: &str) -> bool {
+    let digits = text.strip_prefix(&['-', '+'][..]).unwrap_or(text);
+    match digits.find('.') {
+        Some(dot) => {
+            let (whole, fraction) = (&digits[..dot], &digits[dot + 1..]);
+            !whole.is_empty()
+                && !fraction.is_empty()
+                && whole
+                    .bytes()
+                    .chain(fraction.bytes())
+                    .all(|c| c.is_ascii_digit())
+        }
+        None => false,
     }
 }
 
@@ -131,6 +189,12 @@ impl From<bool> for ValueKind {
     }
 }
 
+impl From<Datetime> for ValueKind {
+    fn from(value: Datetime) -> Self {
+        Self::Datetime(value)
+    }
+}
+
 impl<T> From<Map<String, T>> for ValueKind
 where
     T: Into<Value>,
@@ -162,6 +226,12 @@ impl Display for ValueKind {
             Self::U64(value) => write!(f, "{}", value),
             Self::U128(value) => write!(f, "{}", value),
             Self::Float(value) => write!(f, "{}", value),
+            Self::Decimal(ref value) => write!(f, "{}", value),
+            Self::Bytes(ref value) => match std::str::from_utf8(value) {
+                Ok(value) => write!(f, "{}", value),
+                Err(_) => write!(f, "{}{}", BASE64_PREFIX, encode_base64(value)),
+            },
+            Self::Datetime(value) => write!(f, "{}", value),
             Self::Nil => write!(f, "nil"),
             Self::Table(ref table) => {
                 let mut s = String::new();
@@ -232,6 +302,9 @@ impl Value {
     // FIXME: Should this not be `try_into_*` ?
     pub fn into_bool(self) -> Result<bool> {
         match self.kind {
+            ValueKind::Decimal(ref value) => {
+                Self::new(self.origin.as_ref(), self.decimal_to_float(value)?).into_bool()
+            }
             ValueKind::Boolean(value) => Ok(value),
             ValueKind::I64(value) => Ok(value != 0),
             ValueKind::I128(value) => Ok(value != 0),
@@ -254,6 +327,16 @@ impl Value {
             }
 
             // Unexpected type
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "a boolean",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "a boolean",
+            )),
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Unit,
@@ -276,6 +359,9 @@ impl Value {
     // FIXME: Should this not be `try_into_*` ?
     pub fn into_int(self) -> Result<i64> {
         match self.kind {
+            ValueKind::Decimal(ref value) => {
+                Self::new(self.origin.as_ref(), self.decimal_to_float(value)?).into_int()
+            }
             ValueKind::I64(value) => Ok(value),
             ValueKind::I128(value) => value.try_into().map_err(|_| {
                 ConfigError::invalid_type(
@@ -320,6 +406,16 @@ impl Value {
             ValueKind::Float(value) => Ok(value.round() as i64),
 
             // Unexpected type
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "an integer",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "an integer",
+            )),
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Unit,
@@ -341,6 +437,9 @@ impl Value {
     /// Returns `self` into an i128, if possible.
     pub fn into_int128(self) -> Result<i128> {
         match self.kind {
+            ValueKind::Decimal(ref value) => {
+                Self::new(self.origin.as_ref(), self.decimal_to_float(value)?).into_int128()
+            }
             ValueKind::I64(value) => Ok(value.into()),
             ValueKind::I128(value) => Ok(value),
             ValueKind::U64(value) => Ok(value.into()),
@@ -373,6 +472,16 @@ impl Value {
             ValueKind::Float(value) => Ok(value.round() as i128),
 
             // Unexpected type
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "an integer",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "an integer",
+            )),
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Unit,
@@ -395,6 +504,9 @@ impl Value {
     // FIXME: Should this not be `try_into_*` ?
     pub fn into_uint(self) -> Result<u64> {
         match self.kind {
+            ValueKind::Decimal(ref value) => {
+                Self::new(self.origin.as_ref(), self.decimal_to_float(value)?).into_uint()
+            }
             ValueKind::U64(value) => Ok(value),
             ValueKind::U128(value) => value.try_into().map_err(|_| {
                 ConfigError::invalid_type(
@@ -439,6 +551,16 @@ impl Value {
             ValueKind::Float(value) => Ok(value.round() as u64),
 
             // Unexpected type
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "an integer",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "an integer",
+            )),
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Unit,
@@ -460,6 +582,9 @@ impl Value {
     /// Returns `self` into an u128, if possible.
     pub fn into_uint128(self) -> Result<u128> {
         match self.kind {
+            ValueKind::Decimal(ref value) => {
+                Self::new(self.origin.as_ref(), self.decimal_to_float(value)?).into_uint128()
+            }
             ValueKind::U64(value) => Ok(value.into()),
             ValueKind::U128(value) => Ok(value),
             ValueKind::I64(value) => value.try_into().map_err(|_| {
@@ -498,6 +623,16 @@ impl Value {
             ValueKind::Float(value) => Ok(value.round() as u128),
 
             // Unexpected type
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "an integer",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "an integer",
+            )),
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Unit,
@@ -520,6 +655,7 @@ impl Value {
     // FIXME: Should this not be `try_into_*` ?
     pub fn into_float(self) -> Result<f64> {
         match self.kind {
+            ValueKind::Decimal(ref value) => self.decimal_to_float(value),
             ValueKind::Float(value) => Ok(value),
 
             ValueKind::String(ref s) => {
@@ -546,6 +682,16 @@ impl Value {
             ValueKind::Boolean(value) => Ok(if value { 1.0 } else { 0.0 }),
 
             // Unexpected type
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "a floating point",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "a floating point",
+            )),
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Unit,
@@ -564,6 +710,146 @@ impl Value {
         }
     }
 
+    /// Returns `self` into a duration, if possible.
+    ///
+    /// Strings combine numbers with a unit (`ns`, `us`, `ms`, `s`, `m`, `h` or `d`), as in
+    /// `"1h30m"` or `"1.5s"`. Plain numbers are a number of seconds.
+    pub fn into_duration(self) -> Result<Duration> {
+        match self.kind {
+            ValueKind::Decimal(ref value) => {
+                Self::new(self.origin.as_ref(), self.decimal_to_float(value)?).into_duration()
+            }
+            ValueKind::String(ref s) => parse_duration(s).ok_or_else(|| {
+                ConfigError::invalid_type(
+                    self.origin.clone(),
+                    Unexpected::Str(s.clone()),
+                    "a duration",
+                )
+            }),
+
+            ValueKind::I64(value) => u64::try_from(value).map(Duration::from_secs).map_err(|_| {
+                ConfigError::invalid_type(self.origin, Unexpected::I64(value), "a duration")
+            }),
+            ValueKind::I128(value) => u64::try_from(value).map(Duration::from_secs).map_err(|_| {
+                ConfigError::invalid_type(self.origin, Unexpected::I128(value), "a duration")
+            }),
+            ValueKind::U64(value) => Ok(Duration::from_secs(value)),
+            ValueKind::U128(value) => u64::try_from(value).map(Duration::from_secs).map_err(|_| {
+                ConfigError::invalid_type(self.origin, Unexpected::U128(value), "a duration")
+            }),
+            ValueKind::Float(value) if value >= 0.0 && value < u64::MAX as f64 => {
+                Ok(Duration::from_secs_f64(value))
+            }
+            ValueKind::Float(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Float(value),
+                "a duration",
+            )),
+
+            // Cannot convert
+            ValueKind::Boolean(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bool(value),
+                "a duration",
+            )),
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "a duration",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "a duration",
+            )),
+            ValueKind::Nil => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Unit,
+                "a duration",
+            )),
+            ValueKind::Table(_) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Map,
+                "a duration",
+            )),
+            ValueKind::Array(_) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Seq,
+                "a duration",
+            )),
+        }
+    }
+
+    /// Returns `self` into a number of bytes, if possible.
+    ///
+    /// Strings may carry a decimal (`kB`, `MB`, ...) or binary (`KiB`, `MiB`, ...) unit, as in
+    /// `"512MiB"` or `"1.5 GB"`.
+    pub fn into_byte_size(self) -> Result<u64> {
+        match self.kind {
+            ValueKind::Decimal(ref value) => {
+                Self::new(self.origin.as_ref(), self.decimal_to_float(value)?).into_byte_size()
+            }
+            ValueKind::String(ref s) => parse_byte_size(s).ok_or_else(|| {
+                ConfigError::invalid_type(
+                    self.origin.clone(),
+                    Unexpected::Str(s.clone()),
+                    "a byte size",
+                )
+            }),
+
+            ValueKind::I64(value) => u64::try_from(value).map_err(|_| {
+                ConfigError::invalid_type(self.origin, Unexpected::I64(value), "a byte size")
+            }),
+            ValueKind::I128(value) => u64::try_from(value).map_err(|_| {
+                ConfigError::invalid_type(self.origin, Unexpected::I128(value), "a byte size")
+            }),
+            ValueKind::U64(value) => Ok(value),
+            ValueKind::U128(value) => u64::try_from(value).map_err(|_| {
+                ConfigError::invalid_type(self.origin, Unexpected::U128(value), "a byte size")
+            }),
+            ValueKind::Float(value) if value >= 0.0 && value < u64::MAX as f64 => {
+                Ok(value.round() as u64)
+            }
+            ValueKind::Float(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Float(value),
+                "a byte size",
+            )),
+
+            // Cannot convert
+            ValueKind::Boolean(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bool(value),
+                "a byte size",
+            )),
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "a byte size",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "a byte size",
+            )),
+            ValueKind::Nil => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Unit,
+                "a byte size",
+            )),
+            ValueKind::Table(_) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Map,
+                "a byte size",
+            )),
+            ValueKind::Array(_) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Seq,
+                "a byte size",
+            )),
+        }
+    }
+
     /// Returns `self` into a string, if possible.
     // FIXME: Should this not be `try_into_*` ?
     pub fn into_string(self) -> Result<String> {
@@ -576,6 +862,9 @@ impl Value {
             ValueKind::U64(value) => Ok(value.to_string()),
             ValueKind::U128(value) => Ok(value.to_string()),
             ValueKind::Float(value) => Ok(value.to_string()),
+            ValueKind::Datetime(value) => Ok(value.to_string()),
+            ValueKind::Decimal(value) => Ok(value),
+            ValueKind::Bytes(value) => Ok(ValueKind::Bytes(value).to_string()),
 
             // Cannot convert
             ValueKind::Nil => Err(ConfigError::invalid_type(
@@ -596,6 +885,168 @@ impl Value {
         }
     }
 
+    /// Returns `self` into a datetime, if possible.
+    ///
+    /// Strings holding an RFC 3339 datetime, date or time are parsed, which lets formats without
+    /// a native datetime type such as JSON opt into one on demand.
+    pub fn into_datetime(self) -> Result<Datetime> {
+        match self.kind {
+            ValueKind::Datetime(value) => Ok(value),
+
+            ValueKind::String(ref s) => s.parse().map_err(|_| {
+                ConfigError::invalid_type(
+                    self.origin.clone(),
+                    Unexpected::Str(s.clone()),
+                    "a datetime",
+                )
+            }),
+
+            // Cannot convert
+            ValueKind::Boolean(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bool(value),
+                "a datetime",
+            )),
+            ValueKind::I64(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::I64(value),
+                "a datetime",
+            )),
+            ValueKind::I128(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::I128(value),
+                "a datetime",
+            )),
+            ValueKind::U64(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::U64(value),
+                "a datetime",
+            )),
+            ValueKind::U128(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::U128(value),
+                "a datetime",
+            )),
+            ValueKind::Float(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Float(value),
+                "a datetime",
+            )),
+            ValueKind::Decimal(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Decimal(value),
+                "a datetime",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "a datetime",
+            )),
+            ValueKind::Nil => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Unit,
+                "a datetime",
+            )),
+            ValueKind::Table(_) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Map,
+                "a datetime",
+            )),
+            ValueKind::Array(_) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Seq,
+                "a datetime",
+            )),
+        }
+    }
+
+    /// Returns `self` into bytes, if possible.
+    ///
+    /// Strings prefixed with `base64:` or `hex:` are decoded, other strings are taken as their
+    /// UTF-8 encoding, e.g. the text of a PEM certificate. Arrays of integers in `0..=255` are
+    /// read one byte per element.
+    ///
+    /// Deserializing decodes prefixed strings only into types which ask for bytes, such as
+    /// `serde_bytes::ByteBuf`, and not into a `Vec<u8>` or any other sequence.
+    pub fn into_bytes(self) -> Result<Vec<u8>> {
+        match self.kind {
+            ValueKind::Bytes(value) => Ok(value),
+
+            ValueKind::String(value) => match decode_prefixed(&value) {
+                Some(Ok(bytes)) => Ok(bytes),
+                Some(Err(())) => Err(ConfigError::invalid_type(
+                    self.origin,
+                    Unexpected::Str(value),
+                    "base64 or hex encoded bytes",
+                )),
+                None => Ok(value.into_bytes()),
+            },
+
+            ValueKind::Array(values) => values
+                .into_iter()
+                .map(|value| {
+                    let origin = value.origin.clone();
+                    let byte = value.into_uint()?;
+                    u8::try_from(byte).map_err(|_| {
+                        ConfigError::invalid_type(origin, Unexpected::U64(byte), "a byte")
+                    })
+                })
+                .collect(),
+
+            // Cannot convert
+            ValueKind::Boolean(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bool(value),
+                "bytes",
+            )),
+            ValueKind::I64(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::I64(value),
+                "bytes",
+            )),
+            ValueKind::I128(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::I128(value),
+                "bytes",
+            )),
+            ValueKind::U64(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::U64(value),
+                "bytes",
+            )),
+            ValueKind::U128(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::U128(value),
+                "bytes",
+            )),
+            ValueKind::Float(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Float(value),
+                "bytes",
+            )),
+            ValueKind::Decimal(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Decimal(value),
+                "bytes",
+            )),
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "bytes",
+            )),
+            ValueKind::Nil => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Unit,
+                "bytes",
+            )),
+            ValueKind::Table(_) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Map,
+                "bytes",
+            )),
+        }
+    }
+
     /// Returns `self` into an array, if possible
     // FIXME: Should this not be `try_into_*` ?
     pub fn into_array(self) -> Result<Vec<Self>> {
@@ -638,6 +1089,21 @@ impl Value {
                 Unexpected::Bool(value),
                 "an array",
             )),
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "an array",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "an array",
+            )),
+            ValueKind::Decimal(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Decimal(value),
+                "an array",
+            )),
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Unit,
@@ -693,6 +1159,21 @@ impl Value {
                 Unexpected::Bool(value),
                 "a map",
             )),
+            ValueKind::Datetime(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Datetime(value),
+                "a map",
+            )),
+            ValueKind::Bytes(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bytes(value),
+                "a map",
+            )),
+            ValueKind::Decimal(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Decimal(value),
+                "a map",
+            )),
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Unit,
@@ -705,6 +1186,16 @@ impl Value {
             )),
         }
     }
+
+    fn decimal_to_float(&self, value: &str) -> Result<f64> {
+        value.parse().map_err(|_| {
+            ConfigError::invalid_type(
+                self.origin.clone(),
+                Unexpected::Decimal(value.into()),
+                "a floating point",
+            )
+        })
+    }
 }
 
 impl<'de> Deserialize<'de> for Value {
@@ -768,30 +1259,20 @@ impl<'de> Deserialize<'de> for Value {
             }
 
             #[inline]
-            fn visit_u64<E>(self, value: u64) -> ::std::result::Result<Value, E>
-            where
-                E: ::serde::de::Error,
-            {
-                let num: i64 = value.try_into().map_err(|_| {
-                    E::invalid_type(::serde::de::Unexpected::Unsigned(value), &self)
-                })?;
-                Ok(num.into())
+            fn visit_u64<E>(self, value: u64) -> ::std::result::Result<Value, E> {
+                // Only values beyond `i64` are kept unsigned, as before they were supported
+                match i64::try_from(value) {
+                    Ok(num) => Ok(num.into()),
+                    Err(_) => Ok(value.into()),
+                }
             }
 
             #[inline]
-            fn visit_u128<E>(self, value: u128) -> ::std::result::Result<Value, E>
-            where
-                E: ::serde::de::Error,
-            {
-                let num: i128 = value.try_into().map_err(|_| {
-                    E::invalid_type(
-                        ::serde::de::Unexpected::Other(
-                            format!("integer `{}` as u128", value).as_str(),
-                        ),
-                        &self,
-                    )
-                })?;
-                Ok(num.into())
+            fn visit_u128<E>(self, value: u128) -> ::std::result::Result<Value, E> {
+                match i128::try_from(value) {
+                    Ok(num) => Ok(num.into()),
+                    Err(_) => Ok(value.into()),
+                }
             }
 
             #[inline]
@@ -812,6 +1293,16 @@ impl<'de> Deserialize<'de> for Value {
                 Ok(value.into())
             }
 
+            #[inline]
+            fn visit_bytes<E>(self, value: &[u8]) -> ::std::result::Result<Value, E> {
+                Ok(Value::new(None, ValueKind::Bytes(value.to_vec())))
+            }
+
+            #[inline]
+            fn visit_byte_buf<E>(self, value: Vec<u8>) -> ::std::result::Result<Value, E> {
+                Ok(Value::new(None, ValueKind::Bytes(value)))
+            }
+
             #[inline]
             fn visit_none<E>(self) -> ::std::result::Result<Value, E> {
                 Ok(Value::new(None, ValueKind::Nil))
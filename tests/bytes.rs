@@ -0,0 +1,203 @@
+#![cfg(feature = "json")]
+
+use std::fmt;
+
+use config::{Config, File, FileFormat, Source, Value, ValueKind};
+use serde::de::{Deserializer, Visitor};
+use serde::ser::Serializer;
+use serde_derive::{Deserialize, Serialize};
+
+/// Same as `serde_bytes::ByteBuf`, going through `serialize_bytes` and `deserialize_byte_buf`.
+#[derive(Debug, PartialEq)]
+struct ByteBuf(Vec<u8>);
+
+impl serde::Serialize for ByteBuf {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("bytes")
+            }
+
+            fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(value))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+fn make() -> Config {
+    Config::builder()
+        .add_source(File::from_str(
+            r#"
+            {
+                "pem": "-----BEGIN CERTIFICATE-----",
+                "base64": "base64:3q2+7w==",
+                "urlsafe": "base64:3q2-7w",
+                "hex": "hex:DEAD beef",
+                "array": [222, 173, 190, 239]
+            }
+            "#,
+            FileFormat::Json,
+        ))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_get_bytes() {
+    let c = make();
+
+    assert_eq!(c.get_bytes("pem").unwrap(), b"-----BEGIN CERTIFICATE-----");
+    for key in ["base64", "urlsafe", "hex", "array"] {
+        assert_eq!(
+            c.get_bytes(key).unwrap(),
+            [0xde, 0xad, 0xbe, 0xef],
+            "{}",
+            key
+        );
+    }
+}
+
+#[test]
+fn test_deserialize_bytes() {
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        pem: ByteBuf,
+        base64: ByteBuf,
+        hex: ByteBuf,
+        array: ByteBuf,
+        bytes: Vec<u8>,
+    }
+
+    let c = Config::builder()
+        .add_source(make())
+        .set_override("bytes", vec![222, 173, 190, 239])
+        .unwrap()
+        .build()
+        .unwrap();
+    let s: Settings = c.try_deserialize().unwrap();
+
+    assert_eq!(s.pem, ByteBuf(b"-----BEGIN CERTIFICATE-----".to_vec()));
+    assert_eq!(s.base64, ByteBuf(vec![0xde, 0xad, 0xbe, 0xef]));
+    assert_eq!(s.hex, ByteBuf(vec![0xde, 0xad, 0xbe, 0xef]));
+    assert_eq!(s.array, ByteBuf(vec![0xde, 0xad, 0xbe, 0xef]));
+    assert_eq!(s.bytes, [0xde, 0xad, 0xbe, 0xef]);
+}
+
+#[test]
+fn test_encoded_string_is_not_a_sequence() {
+    let c = Config::builder()
+        .set_override("digest", "hex:0a")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("digest").unwrap(), "hex:0a");
+    for err in [
+        c.get::<Vec<u8>>("digest").unwrap_err(),
+        c.get::<Vec<u32>>("digest").unwrap_err(),
+        c.get::<Vec<String>>("digest").unwrap_err(),
+    ] {
+        assert_eq!(
+            err.to_string(),
+            "invalid type: string \"hex:0a\", expected a sequence"
+        );
+    }
+}
+
+#[test]
+fn test_serialize_bytes() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Settings {
+        key: ByteBuf,
+    }
+
+    let settings = Settings {
+        key: ByteBuf(vec![0, 159, 146, 150]),
+    };
+
+    let c = Config::try_from(&settings).unwrap();
+    assert_eq!(
+        c.collect().unwrap()["key"].kind,
+        ValueKind::Bytes(vec![0, 159, 146, 150])
+    );
+    assert_eq!(c.get::<Vec<u8>>("key").unwrap(), [0, 159, 146, 150]);
+    assert_eq!(c.get_string("key").unwrap(), "base64:AJ+Slg==");
+    assert_eq!(c.try_deserialize::<Settings>().unwrap(), settings);
+
+    let text = Value::new(None, ValueKind::Bytes(b"hello".to_vec()));
+    assert_eq!(text.into_string().unwrap(), "hello");
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_binary() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            "
+            inline: !!binary 3q2+7w==
+            block: !!binary |
+              R0lGODlh
+              AQABAA==
+            ",
+            FileFormat::Yaml,
+        ))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    assert_eq!(
+        values["inline"].kind,
+        ValueKind::Bytes(vec![0xde, 0xad, 0xbe, 0xef])
+    );
+    assert_eq!(c.get_bytes("block").unwrap(), b"GIF89a\x01\x00\x01\x00");
+
+    let res = Config::builder()
+        .add_source(File::from_str("key: !!binary '@@@'", FileFormat::Yaml))
+        .build();
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_error_bytes() {
+    let c = Config::builder()
+        .set_override("key", "base64:not base64!")
+        .unwrap()
+        .set_override("digest", "hex:abc")
+        .unwrap()
+        .set_override("array", vec![1, 256])
+        .unwrap()
+        .set_override("flag", true)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        c.get_bytes("key").unwrap_err().to_string(),
+        "invalid type: string \"base64:not base64!\", expected base64 or hex encoded bytes for key `key`"
+    );
+    assert_eq!(
+        c.get::<ByteBuf>("digest").unwrap_err().to_string(),
+        "invalid type: string \"hex:abc\", expected base64 or hex encoded bytes for key `digest`"
+    );
+    assert_eq!(
+        c.get_bytes("array").unwrap_err().to_string(),
+        "invalid type: 64-bit unsigned integer `256`, expected a byte for key `array`"
+    );
+    assert_eq!(
+        c.get_bytes("flag").unwrap_err().to_string(),
+        "invalid type: boolean `true`, expected bytes for key `flag`"
+    );
+}
@@ -7,7 +7,10 @@
 ))]
 
 use chrono::{DateTime, TimeZone, Utc};
-use config::{Config, File, FileFormat};
+use config::{
+    Config, Date, Datetime, File, FileFormat, Offset, Source, Time, Value, ValueKind, YamlFormat,
+};
+use serde_derive::{Deserialize, Serialize};
 
 fn make() -> Config {
     Config::builder()
@@ -108,3 +111,294 @@ fn test_datetime() {
 
     assert_eq!(date, Utc.with_ymd_and_hms(2021, 4, 19, 11, 33, 2).unwrap());
 }
+
+#[test]
+fn test_toml_datetime_kinds() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            offset = 1979-05-27T07:32:00.5-08:00
+            local = 1979-05-27T07:32:00
+            date = 1979-05-27
+            time = 07:32:00
+            "#,
+            FileFormat::Toml,
+        ))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    assert_eq!(
+        values["offset"].kind,
+        ValueKind::Datetime(Datetime {
+            date: Some(Date {
+                year: 1979,
+                month: 5,
+                day: 27,
+            }),
+            time: Some(Time {
+                hour: 7,
+                minute: 32,
+                second: 0,
+                nanosecond: 500_000_000,
+            }),
+            offset: Some(Offset::Custom { minutes: -480 }),
+        })
+    );
+    assert_eq!(
+        c.get_string("offset").unwrap(),
+        "1979-05-27T07:32:00.5-08:00"
+    );
+
+    let local: Datetime = c.get("local").unwrap();
+    assert_eq!(local.offset, None);
+    assert_eq!(local.to_string(), "1979-05-27T07:32:00");
+
+    let date: Datetime = c.get("date").unwrap();
+    assert_eq!((date.date.is_some(), date.time.is_some()), (true, false));
+    assert_eq!(c.get_string("date").unwrap(), "1979-05-27");
+
+    let time: Datetime = c.get("time").unwrap();
+    assert_eq!((time.date.is_some(), time.time.is_some()), (false, true));
+    assert_eq!(c.get_string("time").unwrap(), "07:32:00");
+
+    assert_eq!(
+        c.get_int("date").unwrap_err().to_string(),
+        "invalid type: datetime `1979-05-27`, expected an integer for key `date`"
+    );
+}
+
+#[test]
+fn test_yaml_timestamps() {
+    let text = r#"
+    at: 2001-12-14t21:59:43.10-05:00
+    day: 2002-12-14
+    quoted: "2002-12-14"
+    tagged: !!timestamp 2002-12-14
+    "#;
+
+    let c = Config::builder()
+        .add_source(File::from_str(text, YamlFormat::new().timestamps(true)))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    for key in ["at", "day", "tagged"] {
+        assert!(
+            matches!(values[key].kind, ValueKind::Datetime(_)),
+            "{}",
+            key
+        );
+    }
+    assert_eq!(c.get_string("at").unwrap(), "2001-12-14T21:59:43.1-05:00");
+
+    assert_eq!(
+        values["quoted"].kind,
+        ValueKind::String("2002-12-14".into())
+    );
+
+    // Without opting in only the explicitly tagged timestamp is a datetime
+    let c = Config::builder()
+        .add_source(File::from_str(text, FileFormat::Yaml))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    assert_eq!(values["day"].kind, ValueKind::String("2002-12-14".into()));
+    assert!(matches!(values["tagged"].kind, ValueKind::Datetime(_)));
+}
+
+#[test]
+fn test_datetime_from_string() {
+    let s = make();
+
+    let date: Datetime = s.get("json_datetime").unwrap();
+    assert_eq!(date.offset, Some(Offset::Z));
+    assert_eq!(
+        s.get::<Value>("json_datetime")
+            .unwrap()
+            .into_datetime()
+            .unwrap(),
+        date
+    );
+
+    let err = Value::from("tomorrow").into_datetime().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: string \"tomorrow\", expected a datetime"
+    );
+}
+
+#[test]
+fn test_datetime_serialize() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Schedule {
+        start: Datetime,
+    }
+
+    let schedule = Schedule {
+        start: "2024-02-29T23:59:60.123+05:30".parse().unwrap(),
+    };
+
+    let c = Config::try_from(&schedule).unwrap();
+    assert_eq!(
+        c.collect().unwrap()["start"].kind,
+        ValueKind::Datetime(schedule.start)
+    );
+    assert_eq!(c.try_deserialize::<Schedule>().unwrap(), schedule);
+}
+
+#[test]
+fn test_datetime_parse_errors() {
+    for invalid in [
+        "2023-02-29",
+        "2023-13-01",
+        "24:00:00",
+        "07:32",
+        "07:32:00Z",
+        "1979-05-27T07:32:00+8",
+        "1979-05-27X07:32:00",
+    ] {
+        assert!(invalid.parse::<Datetime>().is_err(), "{}", invalid);
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono_conversions() {
+    use std::convert::TryFrom;
+
+    use chrono::{FixedOffset, NaiveDate};
+
+    let s = make();
+
+    let toml: Datetime = s.get("toml_datetime").unwrap();
+    let date = DateTime::<FixedOffset>::try_from(toml).unwrap();
+    assert_eq!(date, Utc.with_ymd_and_hms(2017, 5, 11, 14, 55, 15).unwrap());
+    assert_eq!(Datetime::try_from(date).unwrap(), toml);
+
+    assert!(NaiveDate::try_from(toml).is_err());
+    let day = NaiveDate::from_ymd_opt(2017, 5, 11).unwrap();
+    assert_eq!(
+        NaiveDate::try_from(Datetime::try_from(day).unwrap()).unwrap(),
+        day
+    );
+
+    // Out of the range of a datetime rather than wrapped or truncated
+    let ancient = NaiveDate::from_ymd_opt(-44, 3, 15).unwrap();
+    assert!(Datetime::try_from(ancient).is_err());
+    let odd_offset = FixedOffset::east_opt(5 * 3600 + 30 * 60 + 15)
+        .unwrap()
+        .from_utc_datetime(&day.and_hms_opt(12, 0, 0).unwrap());
+    assert!(Datetime::try_from(odd_offset).is_err());
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_time_conversions() {
+    use std::convert::TryFrom;
+
+    use time::OffsetDateTime;
+
+    let s = make();
+
+    let toml: Datetime = s.get("toml_datetime").unwrap();
+    let date = OffsetDateTime::try_from(toml).unwrap();
+    assert_eq!(date.unix_timestamp(), 1_494_514_515);
+    assert_eq!(Datetime::try_from(date).unwrap(), toml);
+
+    // Out of the range of a datetime rather than wrapped or truncated
+    let ancient = time::Date::from_calendar_date(-44, time::Month::March, 15).unwrap();
+    assert!(Datetime::try_from(ancient).is_err());
+    let odd_offset = date.to_offset(time::UtcOffset::from_hms(5, 30, 15).unwrap());
+    assert!(Datetime::try_from(odd_offset).is_err());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_chrono_deserialize() {
+    use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+
+    #[derive(Debug, Deserialize)]
+    struct Schedule {
+        start: DateTime<FixedOffset>,
+        local: NaiveDateTime,
+        day: NaiveDate,
+        at: NaiveTime,
+    }
+
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            start = 2017-05-11T14:55:15+02:00
+            local = 2017-05-11T14:55:15
+            day = 2017-05-11
+            at = 07:32:00.5
+            "#,
+            FileFormat::Toml,
+        ))
+        .build()
+        .unwrap();
+
+    let start = FixedOffset::east_opt(2 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2017, 5, 11, 14, 55, 15)
+        .unwrap();
+    assert_eq!(c.get::<DateTime<FixedOffset>>("start").unwrap(), start);
+
+    let schedule: Schedule = c.try_deserialize().unwrap();
+    assert_eq!(schedule.start, start);
+    assert_eq!(schedule.local, start.naive_local());
+    assert_eq!(schedule.day, NaiveDate::from_ymd_opt(2017, 5, 11).unwrap());
+    assert_eq!(
+        schedule.at,
+        NaiveTime::from_hms_milli_opt(7, 32, 0, 500).unwrap()
+    );
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_time_deserialize() {
+    use time::{Month, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+    #[derive(Debug, Deserialize)]
+    struct Schedule {
+        start: OffsetDateTime,
+        local: PrimitiveDateTime,
+        day: time::Date,
+        at: time::Time,
+    }
+
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            start = 2017-05-11T14:55:15-08:00
+            local = 2017-05-11T14:55:15
+            day = 2017-05-11
+            at = 07:32:00.5
+            "#,
+            FileFormat::Toml,
+        ))
+        .build()
+        .unwrap();
+
+    let day = time::Date::from_calendar_date(2017, Month::May, 11).unwrap();
+    let local = day.with_hms(14, 55, 15).unwrap();
+    let start = local.assume_offset(UtcOffset::from_hms(-8, 0, 0).unwrap());
+    assert_eq!(c.get::<OffsetDateTime>("start").unwrap(), start);
+
+    let schedule: Schedule = c.clone().try_deserialize().unwrap();
+    assert_eq!(schedule.start, start);
+    assert_eq!(schedule.local, local);
+    assert_eq!(schedule.day, day);
+    assert_eq!(
+        schedule.at,
+        time::Time::from_hms_milli(7, 32, 0, 500).unwrap()
+    );
+
+    let err = c.get::<OffsetDateTime>("local").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: datetime `2017-05-11T14:55:15`, expected an offset datetime for key `local`"
+    );
+}
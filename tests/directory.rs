@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::PathBuf;
+
+use config::{Config, Directory, Source, ValueKind};
+
+/// Creates an empty directory unique to the test.
+fn make_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("config-directory-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_directory() {
+    let dir = make_dir("keys");
+    fs::create_dir(dir.join("database")).unwrap();
+    fs::write(dir.join("database").join("host"), "db.local\n").unwrap();
+    fs::write(dir.join("database").join("port"), "5432\n").unwrap();
+    fs::write(dir.join("debug"), "true").unwrap();
+    fs::write(dir.join("zip_code"), "01234").unwrap();
+    fs::write(dir.join("key"), [0xde, 0xad, 0xbe, 0xef]).unwrap();
+    fs::write(dir.join(".hidden"), "skipped").unwrap();
+
+    let values = Directory::new(&dir).collect().unwrap();
+    assert_eq!(values.len(), 5);
+    assert_eq!(
+        values["database.host"].kind,
+        ValueKind::String("db.local".into())
+    );
+    assert_eq!(
+        values["database.port"].kind,
+        ValueKind::String("5432".into())
+    );
+    assert_eq!(
+        values["key"].kind,
+        ValueKind::Bytes(vec![0xde, 0xad, 0xbe, 0xef])
+    );
+
+    let config = Config::builder()
+        .add_source(Directory::new(&dir).try_parsing(true))
+        .build()
+        .unwrap();
+    assert_eq!(config.get_string("database.host").unwrap(), "db.local");
+    assert_eq!(config.get_int("database.port").unwrap(), 5432);
+    assert!(config.get_bool("debug").unwrap());
+    assert_eq!(config.get_float("zip_code").unwrap(), 1234.0);
+    assert!(config.get_string("hidden").is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_directory_kubernetes_volume() {
+    use std::os::unix::fs::symlink;
+
+    // The layout of a mounted ConfigMap, updated by swapping the `..data` symlink
+    let dir = make_dir("volume");
+    let data = dir.join("..2024_01_01_00_00_00.000000000");
+    fs::create_dir(&data).unwrap();
+    fs::write(data.join("username"), "admin").unwrap();
+    fs::write(data.join("password"), "s3cret").unwrap();
+    symlink("..2024_01_01_00_00_00.000000000", dir.join("..data")).unwrap();
+    symlink("..data/username", dir.join("username")).unwrap();
+    symlink("..data/password", dir.join("password")).unwrap();
+
+    let values = Directory::new(&dir).collect().unwrap();
+    let mut keys: Vec<_> = values.keys().cloned().collect();
+    keys.sort();
+    assert_eq!(keys, ["password", "username"]);
+    assert_eq!(values["password"].kind, ValueKind::String("s3cret".into()));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_directory_required() {
+    let dir = std::env::temp_dir().join("config-directory-missing");
+
+    let config = Config::builder()
+        .add_source(Directory::new(&dir).required(false))
+        .build()
+        .unwrap();
+    assert!(config.collect().unwrap().is_empty());
+
+    let err = Config::builder()
+        .add_source(Directory::new(&dir))
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!("configuration directory \"{}\" not found", dir.display())
+    );
+}
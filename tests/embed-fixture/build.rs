@@ -0,0 +1,7 @@
+use config::{embed, ConfigError, FileFormat};
+
+fn main() -> Result<(), ConfigError> {
+    // Files of the same name, which must not overwrite each other
+    embed("defaults/settings.toml", FileFormat::Toml)?;
+    embed("overrides/settings.toml", FileFormat::Toml)
+}
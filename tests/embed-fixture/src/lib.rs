@@ -0,0 +1 @@
+//! Configuration files embedded by the build script, see `tests/include_config.rs`.
@@ -0,0 +1,28 @@
+use config::{include_config, Config, Embedded, File, FileFormat, Source};
+
+#[test]
+fn test_include_config() {
+    let defaults: Embedded = include_config!("defaults/settings.toml");
+    let overrides: Embedded = include_config!("overrides/settings.toml");
+
+    assert_eq!(
+        defaults.collect().unwrap(),
+        File::new("defaults/settings.toml", FileFormat::Toml)
+            .collect()
+            .unwrap()
+    );
+
+    let c = Config::builder()
+        .add_source(defaults)
+        .add_source(overrides)
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("name").unwrap(), "overrides");
+    assert_eq!(c.get_int("port").unwrap(), 8080);
+    assert_eq!(
+        c.get_string("database.url").unwrap(),
+        "postgres://localhost"
+    );
+    assert_eq!(c.get_int("database.pool").unwrap(), 4);
+}
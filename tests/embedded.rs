@@ -0,0 +1,77 @@
+#![cfg(feature = "toml")]
+
+use std::fs;
+
+use config::{embed, Config, Datetime, Embedded, File, FileFormat, Source, ValueKind};
+
+/// Code generated from `tests/Settings-embedded.toml`, as `include_config!` would include it.
+fn embedded() -> Embedded {
+    include!("embedded/Settings-embedded.toml.rs")
+}
+
+#[test]
+fn test_embed_generates_code() {
+    let out_dir = std::env::temp_dir().join(format!("config-embed-{}", std::process::id()));
+    fs::create_dir_all(&out_dir).unwrap();
+
+    temp_env::with_var("OUT_DIR", Some(&out_dir), || {
+        embed("tests/Settings-embedded.toml", FileFormat::Toml).unwrap();
+    });
+
+    assert_eq!(
+        fs::read_to_string(out_dir.join("tests/Settings-embedded.toml.rs")).unwrap(),
+        fs::read_to_string("tests/embedded/Settings-embedded.toml.rs").unwrap()
+    );
+
+    fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn test_embedded_source() {
+    let parsed = File::with_name("tests/Settings-embedded.toml")
+        .collect()
+        .unwrap();
+    let values = embedded().collect().unwrap();
+    assert_eq!(values, parsed);
+    assert_eq!(
+        values["released"].kind,
+        ValueKind::Datetime("1979-05-27T07:32:00Z".parse::<Datetime>().unwrap())
+    );
+
+    let c = Config::builder().add_source(embedded()).build().unwrap();
+    assert_eq!(c.get_string("name").unwrap(), "Torre di \"Pisa\"\n");
+    assert_eq!(c.get_float("place.longitude").unwrap(), 43.7224985);
+    assert_eq!(c.get_string("place.creator.name").unwrap(), "John Smith");
+}
+
+#[test]
+fn test_embed_invalid() {
+    let out_dir = std::env::temp_dir().join(format!("config-embed-invalid-{}", std::process::id()));
+    fs::create_dir_all(&out_dir).unwrap();
+
+    temp_env::with_var("OUT_DIR", Some(&out_dir), || {
+        let err = embed("tests/Settings-invalid.toml", FileFormat::Toml).unwrap_err();
+        assert!(matches!(err, config::ConfigError::FileParse { .. }));
+    });
+    assert!(!out_dir.join("tests/Settings-invalid.toml.rs").exists());
+
+    temp_env::with_var("OUT_DIR", Some(&out_dir), || {
+        assert_eq!(
+            embed("../Settings-embedded.toml", FileFormat::Toml)
+                .unwrap_err()
+                .to_string(),
+            "configuration file \"../Settings-embedded.toml\" must be a relative path without `..`"
+        );
+    });
+
+    temp_env::with_var_unset("OUT_DIR", || {
+        assert_eq!(
+            embed("tests/Settings-embedded.toml", FileFormat::Toml)
+                .unwrap_err()
+                .to_string(),
+            "OUT_DIR is not set, `embed` must run in a build script"
+        );
+    });
+
+    fs::remove_dir_all(&out_dir).unwrap();
+}
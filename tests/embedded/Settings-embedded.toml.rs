@@ -0,0 +1,29 @@
+// Generated by config::embed, do not edit
+{
+    let origin = ::std::string::String::from("tests/Settings-embedded.toml");
+    let origin = ::std::option::Option::Some(&origin);
+    ::config::Embedded::from_values({
+        let mut table = ::config::Map::new();
+        table.insert(::std::string::String::from("code"), ::config::Value::new(origin, ::config::ValueKind::I64(53i64)));
+        table.insert(::std::string::String::from("debug"), ::config::Value::new(origin, ::config::ValueKind::Boolean(true)));
+        table.insert(::std::string::String::from("name"), ::config::Value::new(origin, ::config::ValueKind::String(::std::string::String::from("Torre di \"Pisa\"\n"))));
+        table.insert(::std::string::String::from("place"), ::config::Value::new(origin, ::config::ValueKind::Table({
+            let mut table = ::config::Map::new();
+            table.insert(::std::string::String::from("creator"), ::config::Value::new(origin, ::config::ValueKind::Table({
+                let mut table = ::config::Map::new();
+                table.insert(::std::string::String::from("name"), ::config::Value::new(origin, ::config::ValueKind::String(::std::string::String::from("John Smith"))));
+                table
+            })));
+            table.insert(::std::string::String::from("longitude"), ::config::Value::new(origin, ::config::ValueKind::Float(43.7224985f64)));
+            table.insert(::std::string::String::from("reviews"), ::config::Value::new(origin, ::config::ValueKind::I64(3866i64)));
+            table
+        })));
+        table.insert(::std::string::String::from("ratio"), ::config::Value::new(origin, ::config::ValueKind::Float(4.5f64)));
+        table.insert(::std::string::String::from("released"), ::config::Value::new(origin, ::config::ValueKind::Datetime("1979-05-27T07:32:00Z".parse::<::config::Datetime>().unwrap())));
+        table.insert(::std::string::String::from("tags"), ::config::Value::new(origin, ::config::ValueKind::Array(::std::vec![
+            ::config::Value::new(origin, ::config::ValueKind::String(::std::string::String::from("up"))),
+            ::config::Value::new(origin, ::config::ValueKind::String(::std::string::String::from("down"))),
+        ])));
+        table
+    })
+}
@@ -1,5 +1,6 @@
-use config::{Config, Environment, Source};
+use config::{Config, Environment, FileFormat, Source, TypeHint, ValueKind};
 use serde_derive::Deserialize;
+use std::collections::HashMap;
 
 /// Reminder that tests using env variables need to use different env variable names, since
 /// tests can be run in parallel
@@ -106,7 +107,7 @@ fn test_parse_int() {
     #[derive(Deserialize, Debug)]
     #[serde(tag = "tag")]
     enum TestIntEnum {
-        Int(TestInt),
+        Int(#[allow(dead_code)] TestInt),
     }
 
     #[derive(Deserialize, Debug)]
@@ -169,7 +170,7 @@ fn test_parse_float() {
     #[derive(Deserialize, Debug)]
     #[serde(tag = "tag")]
     enum TestFloatEnum {
-        Float(TestFloat),
+        Float(#[allow(dead_code)] TestFloat),
     }
 
     #[derive(Deserialize, Debug)]
@@ -204,7 +205,7 @@ fn test_parse_bool() {
     #[derive(Deserialize, Debug)]
     #[serde(tag = "tag")]
     enum TestBoolEnum {
-        Bool(TestBool),
+        Bool(#[allow(dead_code)] TestBool),
     }
 
     #[derive(Deserialize, Debug)]
@@ -238,7 +239,7 @@ fn test_parse_off_int() {
     #[derive(Deserialize, Debug)]
     #[serde(tag = "tag")]
     enum TestIntEnum {
-        Int(TestInt),
+        Int(#[allow(dead_code)] TestInt),
     }
 
     #[derive(Deserialize, Debug)]
@@ -268,7 +269,7 @@ fn test_parse_off_float() {
     #[derive(Deserialize, Debug)]
     #[serde(tag = "tag")]
     enum TestFloatEnum {
-        Float(TestFloat),
+        Float(#[allow(dead_code)] TestFloat),
     }
 
     #[derive(Deserialize, Debug)]
@@ -298,7 +299,7 @@ fn test_parse_off_bool() {
     #[derive(Deserialize, Debug)]
     #[serde(tag = "tag")]
     enum TestBoolEnum {
-        Bool(TestBool),
+        Bool(#[allow(dead_code)] TestBool),
     }
 
     #[derive(Deserialize, Debug)]
@@ -328,7 +329,7 @@ fn test_parse_int_fail() {
     #[derive(Deserialize, Debug)]
     #[serde(tag = "tag")]
     enum TestIntEnum {
-        Int(TestInt),
+        Int(#[allow(dead_code)] TestInt),
     }
 
     #[derive(Deserialize, Debug)]
@@ -358,7 +359,7 @@ fn test_parse_float_fail() {
     #[derive(Deserialize, Debug)]
     #[serde(tag = "tag")]
     enum TestFloatEnum {
-        Float(TestFloat),
+        Float(#[allow(dead_code)] TestFloat),
     }
 
     #[derive(Deserialize, Debug)]
@@ -388,7 +389,7 @@ fn test_parse_bool_fail() {
     #[derive(Deserialize, Debug)]
     #[serde(tag = "tag")]
     enum TestBoolEnum {
-        Bool(TestBool),
+        Bool(#[allow(dead_code)] TestBool),
     }
 
     #[derive(Deserialize, Debug)]
@@ -717,3 +718,511 @@ fn test_parse_uint_default() {
     let config: TestUint = config.try_deserialize().unwrap();
     assert_eq!(config.int_val, 42);
 }
+
+#[test]
+fn test_array_indices() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Settings {
+        servers: Vec<Server>,
+        tags: Vec<String>,
+    }
+
+    temp_env::with_vars(
+        vec![
+            ("INDICES__SERVERS__0__HOST", Some("a.example.com")),
+            ("INDICES__SERVERS__0__PORT", Some("80")),
+            ("INDICES__SERVERS__1__HOST", Some("b.example.com")),
+            ("INDICES__SERVERS__1__PORT", Some("8080")),
+            ("INDICES__TAGS__0", Some("blue")),
+            ("INDICES__TAGS__1", Some("green")),
+        ],
+        || {
+            let environment = Environment::with_prefix("INDICES")
+                .separator("__")
+                .array_indices(true)
+                .try_parsing(true);
+
+            let keys = environment.collect().unwrap();
+            assert!(keys.contains_key("servers[1].host"));
+            assert!(keys.contains_key("tags[0]"));
+
+            let settings: Settings = Config::builder()
+                .add_source(environment)
+                .build()
+                .unwrap()
+                .try_deserialize()
+                .unwrap();
+
+            assert_eq!(
+                settings.servers,
+                [
+                    Server {
+                        host: "a.example.com".into(),
+                        port: 80
+                    },
+                    Server {
+                        host: "b.example.com".into(),
+                        port: 8080
+                    },
+                ]
+            );
+            assert_eq!(settings.tags, ["blue", "green"]);
+        },
+    )
+}
+
+#[test]
+fn test_array_indices_override_element() {
+    temp_env::with_var("OVERRIDE__SERVERS__1__PORT", Some("9090"), || {
+        let config = Config::builder()
+            .set_default("servers[0].port", 80)
+            .unwrap()
+            .set_default("servers[1].port", 8080)
+            .unwrap()
+            .set_default("servers[1].host", "b.example.com")
+            .unwrap()
+            .add_source(
+                Environment::with_prefix("OVERRIDE")
+                    .separator("__")
+                    .array_indices(true),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(config.get_int("servers[0].port").unwrap(), 80);
+        assert_eq!(config.get_int("servers[1].port").unwrap(), 9090);
+        assert_eq!(
+            config.get_string("servers[1].host").unwrap(),
+            "b.example.com"
+        );
+    })
+}
+
+#[test]
+fn test_type_hints() {
+    temp_env::with_vars(
+        vec![
+            ("HINTS_ZIP_CODE", Some("01234")),
+            ("HINTS_PASSWORD", Some("true")),
+            ("HINTS_PORT", Some("8080")),
+            ("HINTS_RATIO", Some("2")),
+            ("HINTS_VERBOSE", Some("YES")),
+            ("HINTS_DEBUG", Some("TRUE")),
+            ("HINTS_HOSTS", Some("a;b;c")),
+            ("HINTS_TAG_A", Some("10")),
+            ("HINTS_TAG_B", Some("20")),
+        ],
+        || {
+            let environment = Environment::with_prefix("HINTS")
+                .with_type_hint("zip_code", TypeHint::String)
+                .with_type_hint("password", TypeHint::String)
+                .with_type_hint("port", TypeHint::Int)
+                .with_type_hint("ratio", TypeHint::Float)
+                .with_type_hint("debug", TypeHint::Bool)
+                .with_type_hint("hosts", TypeHint::List(";".into()))
+                .with_type_hint("tag_*", TypeHint::Int);
+
+            let values = environment.collect().unwrap();
+            assert_eq!(values["zip_code"].kind, ValueKind::String("01234".into()));
+            assert_eq!(values["password"].kind, ValueKind::String("true".into()));
+            assert_eq!(values["port"].kind, ValueKind::I64(8080));
+            assert_eq!(values["ratio"].kind, ValueKind::Float(2.0));
+            assert_eq!(values["debug"].kind, ValueKind::Boolean(true));
+            assert_eq!(values["verbose"].kind, ValueKind::String("YES".into()));
+            assert_eq!(values["tag_a"].kind, ValueKind::I64(10));
+            assert_eq!(values["tag_b"].kind, ValueKind::I64(20));
+
+            let config = Config::builder().add_source(environment).build().unwrap();
+            assert_eq!(config.get::<Vec<String>>("hosts").unwrap(), ["a", "b", "c"]);
+        },
+    )
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_type_hint_json() {
+    temp_env::with_var(
+        "JSONHINT_LIMITS",
+        Some(r#"{"cpu": 2, "memory": "1Gi"}"#),
+        || {
+            let config = Config::builder()
+                .add_source(
+                    Environment::with_prefix("JSONHINT").with_type_hint("limits", TypeHint::Json),
+                )
+                .build()
+                .unwrap();
+
+            assert_eq!(config.get_int("limits.cpu").unwrap(), 2);
+            assert_eq!(config.get_string("limits.memory").unwrap(), "1Gi");
+        },
+    )
+}
+
+#[test]
+fn test_type_hints_override_try_parsing() {
+    temp_env::with_vars(
+        vec![
+            ("HINTED_ZIP_CODE", Some("01234")),
+            ("HINTED_SECRET", Some("false")),
+            ("HINTED_OTHER", Some("false")),
+        ],
+        || {
+            let values = Environment::with_prefix("HINTED")
+                .try_parsing(true)
+                .with_type_hint("zip_code", TypeHint::String)
+                .with_type_hint("SECRET", TypeHint::String)
+                .collect()
+                .unwrap();
+
+            assert_eq!(values["zip_code"].kind, ValueKind::String("01234".into()));
+            assert_eq!(values["secret"].kind, ValueKind::String("false".into()));
+            assert_eq!(values["other"].kind, ValueKind::Boolean(false));
+        },
+    )
+}
+
+#[test]
+fn test_type_hint_error() {
+    temp_env::with_var("BADHINT_PORT", Some("eighty"), || {
+        let err = Environment::with_prefix("BADHINT")
+            .with_type_hint("port", TypeHint::Int)
+            .collect()
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "invalid type: string \"eighty\", expected an integer for key `port` in the environment"
+        );
+    })
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_value_format_merges_subtree() {
+    temp_env::with_vars(
+        vec![
+            (
+                "STRUCTURED_FEATURE_FLAGS",
+                Some(r#"{"a": true, "b": [1, 2]}"#),
+            ),
+            ("STRUCTURED_NAME", Some("plain")),
+        ],
+        || {
+            let config = Config::builder()
+                .set_default("feature_flags.c", "kept")
+                .unwrap()
+                .add_source(
+                    Environment::with_prefix("STRUCTURED")
+                        .with_value_format("feature_*", FileFormat::Json),
+                )
+                .build()
+                .unwrap();
+
+            assert!(config.get_bool("feature_flags.a").unwrap());
+            assert_eq!(config.get::<Vec<i32>>("feature_flags.b").unwrap(), [1, 2]);
+            assert_eq!(config.get_string("feature_flags.c").unwrap(), "kept");
+            assert_eq!(config.get_string("name").unwrap(), "plain");
+        },
+    )
+}
+
+#[cfg(all(feature = "toml", feature = "yaml"))]
+#[test]
+fn test_value_format_toml_and_yaml() {
+    temp_env::with_vars(
+        vec![
+            ("INLINE_LIMITS", Some("{ cpu = 2, memory = \"1Gi\" }")),
+            ("INLINE_PORTS", Some("[80, 443]")),
+        ],
+        || {
+            let config = Config::builder()
+                .add_source(Environment::with_prefix("INLINE").value_format(FileFormat::Toml))
+                .build()
+                .unwrap();
+
+            assert_eq!(config.get_int("limits.cpu").unwrap(), 2);
+            assert_eq!(config.get_string("limits.memory").unwrap(), "1Gi");
+            assert_eq!(config.get::<Vec<u16>>("ports").unwrap(), [80, 443]);
+        },
+    );
+
+    temp_env::with_vars(
+        vec![
+            ("FLOW_LIMITS", Some("{cpu: 2, memory: 1Gi}")),
+            ("FLOW_PORTS", Some("[80, 443]")),
+        ],
+        || {
+            let config = Config::builder()
+                .add_source(Environment::with_prefix("FLOW").value_format(FileFormat::Yaml))
+                .build()
+                .unwrap();
+
+            assert_eq!(config.get_int("limits.cpu").unwrap(), 2);
+            assert_eq!(config.get_string("limits.memory").unwrap(), "1Gi");
+            assert_eq!(config.get::<Vec<u16>>("ports").unwrap(), [80, 443]);
+        },
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_value_format_error() {
+    temp_env::with_var("BADJSON_FLAGS", Some("{not json"), || {
+        let err = Environment::with_prefix("BADJSON")
+            .value_format(FileFormat::Json)
+            .collect()
+            .unwrap_err();
+
+        match err {
+            config::ConfigError::FileParse { uri, .. } => {
+                assert_eq!(uri.as_deref(), Some("BADJSON_FLAGS"));
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+    })
+}
+
+#[test]
+fn test_key_mapper() {
+    temp_env::with_vars(
+        vec![
+            ("MAPPED_DB_HOST", Some("db.local")),
+            ("MAPPED_DB_PORT", Some("5432")),
+            ("MAPPED_INTERNAL_TOKEN", Some("secret")),
+        ],
+        || {
+            let environment = Environment::with_prefix("MAPPED")
+                .try_parsing(true)
+                .with_type_hint("database.port", TypeHint::String)
+                .key_mapper(|key| {
+                    if key.starts_with("internal") {
+                        None
+                    } else {
+                        Some(key.replacen("db_", "database.", 1))
+                    }
+                });
+
+            let values = environment.collect().unwrap();
+            assert_eq!(values.len(), 2);
+            assert_eq!(
+                values["database.host"].kind,
+                ValueKind::String("db.local".into())
+            );
+            assert_eq!(
+                values["database.port"].kind,
+                ValueKind::String("5432".into())
+            );
+        },
+    )
+}
+
+#[test]
+fn test_value_transformer() {
+    temp_env::with_vars(
+        vec![
+            ("TRANSFORM_PASSWORD", Some("enc:terces")),
+            ("TRANSFORM_USER", Some("admin")),
+        ],
+        || {
+            let environment =
+                Environment::with_prefix("TRANSFORM").value_transformer(|key, value| {
+                    let text = value.clone().into_string()?;
+                    match text.strip_prefix("enc:") {
+                        Some(encrypted) if key == "password" => {
+                            Ok(encrypted.chars().rev().collect::<String>().into())
+                        }
+                        Some(_) => Err(format!("`{}` cannot be encrypted", key).into()),
+                        None => Ok(value),
+                    }
+                });
+
+            let config = Config::builder()
+                .add_source(environment.clone())
+                .build()
+                .unwrap();
+            assert_eq!(config.get_string("password").unwrap(), "secret");
+            assert_eq!(config.get_string("user").unwrap(), "admin");
+
+            temp_env::with_var("TRANSFORM_USER", Some("enc:nimda"), || {
+                let err = environment.collect().unwrap_err();
+                assert_eq!(
+                    err.to_string(),
+                    "`user` cannot be encrypted in TRANSFORM_USER"
+                );
+            });
+        },
+    )
+}
+
+#[test]
+fn test_file_suffix() {
+    let dir = std::env::temp_dir().join(format!("config-env-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let password = dir.join("db_password");
+    std::fs::write(&password, "s3cret\r\n\n").unwrap();
+    let port = dir.join("db_port");
+    std::fs::write(&port, "5432\n").unwrap();
+
+    temp_env::with_vars(
+        vec![
+            ("SECRET_DB_PASSWORD_FILE", password.to_str()),
+            ("SECRET_DB_PASSWORD", Some("ignored")),
+            ("SECRET_DB_PORT_file", port.to_str()),
+            ("SECRET_DB_USER", Some("admin")),
+        ],
+        || {
+            let values = Environment::with_prefix("SECRET")
+                .separator("_")
+                .try_parsing(true)
+                .file_suffix("_FILE")
+                .collect()
+                .unwrap();
+
+            assert_eq!(values.len(), 3);
+            assert_eq!(
+                values["db.password"].kind,
+                ValueKind::String("s3cret".into())
+            );
+            assert_eq!(values["db.port"].kind, ValueKind::I64(5432));
+            assert_eq!(values["db.user"].kind, ValueKind::String("admin".into()));
+
+            let values = Environment::with_prefix("SECRET")
+                .separator("_")
+                .collect()
+                .unwrap();
+            assert_eq!(
+                values["db.password"].kind,
+                ValueKind::String("ignored".into())
+            );
+            assert!(values.contains_key("db.password.file"));
+        },
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_error_file_suffix() {
+    temp_env::with_var(
+        "MISSING_KEY_FILE",
+        Some("/nonexistent/config-rs/secret"),
+        || {
+            let err = Environment::with_prefix("MISSING")
+                .file_suffix("_FILE")
+                .collect()
+                .unwrap_err()
+                .to_string();
+
+            assert!(
+                err.starts_with("could not read `/nonexistent/config-rs/secret`: "),
+                "{}",
+                err
+            );
+            assert!(err.ends_with(" in MISSING_KEY_FILE"), "{}", err);
+        },
+    )
+}
+
+#[test]
+fn test_file_suffix_dropped_by_key_mapper() {
+    temp_env::with_vars(
+        vec![
+            ("DROPPED_UNUSED_FILE", Some("/nonexistent/config-rs/unused")),
+            ("DROPPED_NAME", Some("app")),
+        ],
+        || {
+            let values = Environment::with_prefix("DROPPED")
+                .file_suffix("_FILE")
+                .key_mapper(|key| {
+                    if key == "unused" {
+                        None
+                    } else {
+                        Some(key.to_owned())
+                    }
+                })
+                .collect()
+                .unwrap();
+
+            assert_eq!(values.len(), 1);
+            assert_eq!(values["name"].kind, ValueKind::String("app".into()));
+        },
+    )
+}
+
+#[test]
+fn test_array_indices_disabled() {
+    #[derive(Deserialize, Debug)]
+    struct Port {
+        name: String,
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Settings {
+        ports: HashMap<u16, Port>,
+    }
+
+    temp_env::with_var("DISABLED__PORTS__8080__NAME", Some("http"), || {
+        let settings: Settings = Config::builder()
+            .add_source(Environment::with_prefix("DISABLED").separator("__"))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        assert_eq!(settings.ports[&8080].name, "http");
+    })
+}
+
+#[test]
+fn test_array_indices_out_of_range() {
+    temp_env::with_var("HUGE__LIST__4000000000", Some("x"), || {
+        let error = Environment::with_prefix("HUGE")
+            .separator("__")
+            .array_indices(true)
+            .collect()
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "array index 4000000000 is larger than 1024 in HUGE__LIST__4000000000"
+        );
+    })
+}
+
+#[test]
+fn test_array_indices_sparse() {
+    temp_env::with_vars(
+        vec![
+            ("GAP__LIST__3", Some("d")),
+            ("GAP__LIST__1", Some("b")),
+            ("GAP__SERVERS__10__HOST", Some("k.example.com")),
+        ],
+        || {
+            let config = Config::builder()
+                .add_source(
+                    Environment::with_prefix("GAP")
+                        .separator("__")
+                        .array_indices(true),
+                )
+                .build()
+                .unwrap();
+
+            // The elements not set are padded with nil values
+            assert_eq!(
+                config.get::<Vec<Option<String>>>("list").unwrap(),
+                [None, Some("b".into()), None, Some("d".into())]
+            );
+            assert_eq!(config.get_array("servers").unwrap().len(), 11);
+            assert_eq!(
+                config.get_string("servers[10].host").unwrap(),
+                "k.example.com"
+            );
+        },
+    )
+}
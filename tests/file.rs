@@ -1,6 +1,8 @@
 #![cfg(feature = "yaml")]
 
-use config::{Config, File, FileFormat};
+use config::{
+    Config, ConfigError, File, FileFormat, FileLookupError, FileSourceFile, SearchPolicy,
+};
 
 #[test]
 fn test_file_not_required() {
@@ -11,6 +13,41 @@ fn test_file_not_required() {
     assert!(res.is_ok());
 }
 
+/// Returns `path` followed by the same path compressed with each compression enabled, in the
+/// order they are probed.
+fn probed_paths(path: std::path::PathBuf) -> Vec<std::path::PathBuf> {
+    let mut paths = vec![path.clone()];
+    for (enabled, ext) in [
+        (cfg!(feature = "gzip"), "gz"),
+        (cfg!(feature = "zstd"), "zst"),
+    ] {
+        if enabled {
+            let mut compressed = path.clone().into_os_string();
+            compressed.push(".");
+            compressed.push(ext);
+            paths.push(compressed.into());
+        }
+    }
+    paths
+}
+
+/// Returns the paths probed as quoted in a not-found error.
+fn tried(paths: &[std::path::PathBuf]) -> String {
+    let quoted: Vec<String> = paths
+        .iter()
+        .map(|path| format!("\"{}\"", path.display()))
+        .collect();
+    quoted.join(", ")
+}
+
+/// Returns the error of a file that could not be looked up.
+fn lookup_error(error: &ConfigError) -> &FileLookupError {
+    match error {
+        ConfigError::Foreign(cause) => cause.downcast_ref().unwrap(),
+        _ => panic!("unexpected error: {}", error),
+    }
+}
+
 #[test]
 fn test_file_required_not_found() {
     let res = Config::builder()
@@ -18,10 +55,14 @@ fn test_file_required_not_found() {
         .build();
 
     assert!(res.is_err());
-    assert_eq!(
-        res.unwrap_err().to_string(),
-        "configuration file \"tests/NoSettings\" not found".to_string()
-    );
+    let base = std::env::current_dir().unwrap().join("tests/NoSettings");
+    let mut paths = probed_paths(base.clone());
+    paths.extend(probed_paths(base.with_extension("yaml")));
+    paths.extend(probed_paths(base.with_extension("yml")));
+    assert!(res.unwrap_err().to_string().starts_with(&format!(
+        "configuration file \"tests/NoSettings\" not found, tried {} (registered extensions: ",
+        tried(&paths)
+    )));
 }
 
 #[test]
@@ -41,11 +82,31 @@ fn test_file_auto_not_found() {
         .add_source(File::with_name("tests/NoSettings"))
         .build();
 
-    assert!(res.is_err());
-    assert_eq!(
-        res.unwrap_err().to_string(),
-        "configuration file \"tests/NoSettings\" not found".to_string()
-    );
+    let err = res.unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("configuration file \"tests/NoSettings\" not found, tried "));
+
+    match lookup_error(&err) {
+        FileLookupError::NotFound {
+            name,
+            probed,
+            extensions,
+        } => {
+            let base = std::env::current_dir().unwrap().join("tests/NoSettings");
+            assert_eq!(name, std::path::Path::new("tests/NoSettings"));
+            assert_eq!(extensions[0], "toml");
+            assert!(extensions.contains(&"yml"));
+
+            // Probed in order of precedence
+            let mut expected = probed_paths(base.clone());
+            for ext in extensions {
+                expected.extend(probed_paths(base.with_extension(ext)));
+            }
+            assert_eq!(probed, &expected);
+        }
+        error => panic!("unexpected error: {}", error),
+    }
 }
 
 #[test]
@@ -68,3 +129,135 @@ fn test_file_second_ext() {
     assert_eq!(c.get("debug").ok(), Some(true));
     assert_eq!(c.get("production").ok(), Some(false));
 }
+
+#[test]
+fn test_file_search_paths() {
+    let file = File::with_name("app")
+        .search_path("tests/search-paths/missing")
+        .search_paths(vec![
+            "tests/search-paths/local",
+            "tests/search-paths/system",
+        ]);
+
+    let c = Config::builder().add_source(file.clone()).build().unwrap();
+
+    assert_eq!(c.get("debug").ok(), Some(true));
+    assert_eq!(c.get("database.host").ok(), Some("localhost".to_owned()));
+    assert_eq!(c.get::<bool>("system").ok(), None);
+
+    let c = Config::builder()
+        .add_source(file.search_policy(SearchPolicy::MergeAll))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("debug").ok(), Some(true));
+    assert_eq!(c.get("system").ok(), Some(true));
+    assert_eq!(c.get("database.host").ok(), Some("localhost".to_owned()));
+    assert_eq!(c.get("database.port").ok(), Some(5432));
+}
+
+#[test]
+fn test_file_search_paths_not_found() {
+    let file = File::new("app", FileFormat::Yaml)
+        .search_path("tests/search-paths/missing")
+        .search_path("/etc/config-rs");
+
+    let cwd = std::env::current_dir().unwrap();
+    assert_eq!(
+        file.candidates(),
+        [
+            cwd.join("tests/search-paths/missing/app"),
+            std::path::PathBuf::from("/etc/config-rs/app")
+        ]
+    );
+
+    let mut paths = Vec::new();
+    for base in file.candidates() {
+        paths.extend(probed_paths(base.clone()));
+        paths.extend(probed_paths(base.with_extension("yaml")));
+        paths.extend(probed_paths(base.with_extension("yml")));
+    }
+
+    let res = Config::builder().add_source(file).build();
+    assert!(res.unwrap_err().to_string().starts_with(&format!(
+        "configuration file \"app\" not found, tried {} (registered extensions: ",
+        tried(&paths)
+    )));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_file_format_precedence() {
+    let format = |file: File<FileSourceFile, FileFormat>| {
+        Config::builder()
+            .add_source(file)
+            .build()
+            .unwrap()
+            .get_string("format")
+            .unwrap()
+    };
+
+    let name = "tests/search-paths/ambiguous/app";
+    assert_eq!(format(File::with_name(name)), "toml");
+    assert_eq!(
+        format(File::with_name(name).format_precedence(&[&FileFormat::Yaml])),
+        "yaml"
+    );
+    assert_eq!(format(File::new(name, FileFormat::Yaml)), "yaml");
+}
+
+#[test]
+fn test_file_ambiguous_matches() {
+    let cwd = std::env::current_dir().unwrap();
+    let dir = cwd.join("tests/search-paths/ambiguous");
+
+    // The file with precedence is read, and the others are recorded
+    let resolved = File::with_name("tests/search-paths/ambiguous/app")
+        .resolve()
+        .unwrap();
+    assert_eq!(
+        resolved.ambiguous_matches(),
+        [dir.join("app.yaml"), dir.join("app.yml")]
+    );
+
+    let resolved = File::with_name("tests/search-paths/local/app")
+        .resolve()
+        .unwrap();
+    assert!(resolved.ambiguous_matches().is_empty());
+}
+
+#[test]
+fn test_file_error_on_ambiguity() {
+    let cwd = std::env::current_dir().unwrap();
+    let yaml = cwd.join("tests/search-paths/ambiguous/app.yaml");
+    let yml = cwd.join("tests/search-paths/ambiguous/app.yml");
+
+    let file = File::new("tests/search-paths/ambiguous/app", FileFormat::Yaml)
+        .required(false)
+        .error_on_ambiguity(true);
+    let err = Config::builder().add_source(file).build().unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "configuration file \"tests/search-paths/ambiguous/app\" is ambiguous, found \"{}\", \"{}\"",
+            yaml.display(),
+            yml.display()
+        )
+    );
+    match lookup_error(&err) {
+        FileLookupError::Ambiguous { matches, .. } => {
+            assert_eq!(matches, &[yaml.clone(), yml.clone()])
+        }
+        error => panic!("unexpected error: {}", error),
+    }
+
+    let err = Config::builder()
+        .add_source(File::with_name("tests/search-paths/ambiguous/app").error_on_ambiguity(true))
+        .build()
+        .unwrap_err();
+    match lookup_error(&err) {
+        FileLookupError::Ambiguous { matches, .. } => assert_eq!(matches.len(), 3),
+        error => panic!("unexpected error: {}", error),
+    }
+}
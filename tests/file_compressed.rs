@@ -0,0 +1,132 @@
+#![cfg(all(feature = "json", any(feature = "gzip", feature = "zstd")))]
+
+use config::{Config, ConfigError, File, FileFormat, FileLookupError, FileStoredFormat};
+
+const COMPRESSED_EXTENSIONS: &[&str] = &[
+    #[cfg(feature = "gzip")]
+    "gz",
+    #[cfg(feature = "zstd")]
+    "zst",
+];
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_file_gzip() {
+    // Found with the extension of the format and then that of the compression
+    let c = Config::builder()
+        .add_source(File::with_name("tests/compressed/routes"))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("routes.users").unwrap(), "/users");
+    assert_eq!(c.get_int("timeout").unwrap(), 30);
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_file_gzip_inner_extension() {
+    for name in &[
+        "tests/compressed/routes.json.gz",
+        // The compressed file is found for the name of the file it holds
+        "tests/compressed/routes.json",
+    ] {
+        let c = Config::builder()
+            .add_source(File::with_name(name))
+            .build()
+            .unwrap();
+
+        assert_eq!(c.get_string("routes.home").unwrap(), "/");
+    }
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_file_gzip_magic_bytes() {
+    let c = Config::builder()
+        .add_source(File::new("tests/compressed/bundle", FileFormat::Json))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_int("timeout").unwrap(), 30);
+
+    let file = File::with_name("tests/compressed/bundle").detect_format(true);
+    assert_eq!(
+        file.resolve()
+            .unwrap()
+            .detected_format()
+            .map(|format| format.file_extensions()),
+        Some(FileFormat::Json.file_extensions())
+    );
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_file_gzip_from_bytes() {
+    let bytes = std::fs::read("tests/compressed/routes.json.gz").unwrap();
+    let c = Config::builder()
+        .add_source(File::from_bytes("routes.json.gz", bytes, FileFormat::Json))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("routes.users").unwrap(), "/users");
+}
+
+#[test]
+fn test_file_compressed_not_found() {
+    let res = Config::builder()
+        .add_source(File::new("tests/compressed/missing", FileFormat::Json))
+        .build();
+
+    let probed = match res.unwrap_err() {
+        ConfigError::Foreign(cause) => match cause.downcast_ref() {
+            Some(FileLookupError::NotFound { probed, .. }) => probed.clone(),
+            _ => panic!("unexpected error: {}", cause),
+        },
+        error => panic!("unexpected error: {}", error),
+    };
+
+    // Each path is followed by the compressed files it can be stored as
+    let base = std::env::current_dir()
+        .unwrap()
+        .join("tests/compressed/missing");
+    let mut expected = Vec::new();
+    for path in &[base.clone(), base.with_extension("json")] {
+        expected.push(path.clone());
+        for ext in COMPRESSED_EXTENSIONS {
+            let mut compressed = path.clone().into_os_string();
+            compressed.push(".");
+            compressed.push(ext);
+            expected.push(compressed.into());
+        }
+    }
+    assert_eq!(probed, expected);
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+fn test_error_file_gzip() {
+    let res = Config::builder()
+        .add_source(File::with_name("tests/compressed/broken.json.gz"))
+        .build();
+
+    let message = res.unwrap_err().to_string();
+    assert!(
+        message.starts_with(
+            "configuration file \"tests/compressed/broken.json.gz\" could not be decompressed: "
+        ),
+        "{}",
+        message
+    );
+}
+
+#[test]
+#[cfg(all(feature = "zstd", feature = "toml"))]
+fn test_file_zstd() {
+    let c = Config::builder()
+        .add_source(File::with_name("tests/compressed/settings"))
+        .build()
+        .unwrap();
+
+    assert!(c.get_bool("debug").unwrap());
+    assert_eq!(c.get_int("server.port").unwrap(), 8080);
+}
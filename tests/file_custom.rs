@@ -0,0 +1,187 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use config::{
+    register_format, unregister_format, Config, ConfigError, File, FileLookupError,
+    FileSourceBytes, FileStoredFormat, Format, Map, Value, ValueKind,
+};
+
+/// Lines of `key=value`, the values being strings.
+#[derive(Debug, Clone)]
+struct PropertiesFile;
+
+impl Format for PropertiesFile {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+        let mut map = Map::new();
+        for line in text.lines() {
+            let split = line.find('=').ok_or("expected `key=value`")?;
+            map.insert(
+                line[..split].to_owned(),
+                Value::new(uri, ValueKind::String(line[split + 1..].to_owned())),
+            );
+        }
+
+        Ok(map)
+    }
+}
+
+impl FileStoredFormat for PropertiesFile {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        &["props", "properties"]
+    }
+}
+
+/// Properties under an extension of their own, which only one test registers.
+#[derive(Debug, Clone)]
+struct AliasFile;
+
+impl Format for AliasFile {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+        PropertiesFile.parse(uri, text)
+    }
+}
+
+impl FileStoredFormat for AliasFile {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        &["alias"]
+    }
+}
+
+/// Text starting with `}}`, which no format of the library accepts, so it is only ever detected
+/// from the content of a file as this format.
+#[derive(Debug, Clone)]
+struct MarkedFile;
+
+impl Format for MarkedFile {
+    fn parse(
+        &self,
+        uri: Option<&String>,
+        text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+        let text = text.strip_prefix("}}").ok_or("expected `}}`")?;
+        let mut map = Map::new();
+        map.insert(
+            "marked".to_owned(),
+            Value::new(uri, ValueKind::String(text.trim().to_owned())),
+        );
+
+        Ok(map)
+    }
+}
+
+impl FileStoredFormat for MarkedFile {
+    fn file_extensions(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+#[test]
+fn test_file_custom_format() {
+    let registered = register_format(PropertiesFile);
+
+    for file in [
+        File::with_name("tests/Settings-custom"),
+        File::with_name("tests/Settings-custom.props"),
+        File::from(Path::new("tests/Settings-custom.props")),
+        File::from(PathBuf::from("tests/Settings-custom")),
+    ] {
+        let c = Config::builder().add_source(file).build().unwrap();
+
+        assert_eq!(c.get("debug").ok(), Some(true));
+        assert_eq!(c.get("name").ok(), Some("custom".to_owned()));
+    }
+
+    let err = Config::builder()
+        .add_source(File::with_name("tests/NoSettings"))
+        .build()
+        .unwrap_err();
+    match err {
+        ConfigError::Foreign(cause) => match cause.downcast_ref() {
+            Some(FileLookupError::NotFound { extensions, .. }) => {
+                // Other tests may register formats of their own meanwhile
+                let props = extensions.iter().position(|ext| *ext == "props").unwrap();
+                assert_eq!(extensions[props..props + 2], ["props", "properties"]);
+            }
+            _ => panic!("unexpected error: {}", cause),
+        },
+        _ => panic!("unexpected error: {}", err),
+    }
+
+    unregister_format(registered);
+}
+
+#[test]
+fn test_file_custom_format_precedence() {
+    let registered = register_format(PropertiesFile);
+
+    let format = |file: File<_, _>| {
+        Config::builder()
+            .add_source(file)
+            .build()
+            .unwrap()
+            .get_string("format")
+            .unwrap()
+    };
+
+    let name = "tests/custom-precedence/app";
+    if cfg!(feature = "toml") {
+        assert_eq!(format(File::with_name(name)), "toml");
+    }
+    assert_eq!(
+        format(File::with_name(name).format_precedence(&[&PropertiesFile])),
+        "props"
+    );
+
+    unregister_format(registered);
+}
+
+#[test]
+fn test_unregister_format() {
+    let registered = register_format(AliasFile);
+
+    let file = || File::with_name("tests/Settings-custom-alias");
+    let c = Config::builder().add_source(file()).build().unwrap();
+    assert_eq!(c.get_string("name").unwrap(), "alias");
+
+    assert!(unregister_format(registered));
+    assert!(!unregister_format(registered));
+    assert!(Config::builder().add_source(file()).build().is_err());
+}
+
+#[test]
+fn test_file_custom_format_without_extensions() {
+    let registered = register_format(MarkedFile);
+
+    let file = File::with_name("tests/detect/marked").detect_format(true);
+    let resolved = file.resolve().unwrap();
+    let detected = resolved.detected_format().unwrap();
+    assert!(detected.file_extensions().is_empty());
+
+    let c = Config::builder().add_source(file).build().unwrap();
+    assert_eq!(c.get_string("marked").unwrap(), "marked");
+
+    unregister_format(registered);
+}
+
+#[test]
+fn test_bytes_custom_format_without_extensions() {
+    let registered = register_format(MarkedFile);
+
+    let file = File::from(FileSourceBytes::new("embedded", "}} in memory"));
+    let resolved = file.resolve().unwrap();
+    let detected = resolved.detected_format().unwrap();
+    assert!(detected.file_extensions().is_empty());
+
+    let c = Config::builder().add_source(file).build().unwrap();
+    assert_eq!(c.get_string("marked").unwrap(), "in memory");
+
+    unregister_format(registered);
+}
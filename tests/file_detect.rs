@@ -0,0 +1,62 @@
+#![cfg(all(feature = "toml", feature = "json", feature = "yaml"))]
+
+use config::{Config, File, FileFormat, FileStoredFormat};
+
+#[test]
+fn test_file_detect_format() {
+    for (name, format) in [
+        ("tests/detect/yaml", FileFormat::Yaml),
+        ("tests/detect/json", FileFormat::Json),
+        ("tests/detect/modeline.conf", FileFormat::Toml),
+        ("tests/detect/shebang", FileFormat::Toml),
+        ("tests/detect/toml.conf", FileFormat::Toml),
+    ] {
+        let file = File::with_name(name).detect_format(true);
+        assert_eq!(
+            file.resolve()
+                .unwrap()
+                .detected_format()
+                .map(|format| format.file_extensions()),
+            Some(format.file_extensions()),
+            "{}",
+            name
+        );
+
+        let c = Config::builder().add_source(file).build().unwrap();
+        assert_eq!(c.get_int("server.port").unwrap(), 8080, "{}", name);
+    }
+
+    let file = File::with_name("tests/Settings.toml").detect_format(true);
+    assert!(file.resolve().unwrap().detected_format().is_none());
+}
+
+#[test]
+fn test_file_detect_format_disabled() {
+    let res = Config::builder()
+        .add_source(File::with_name("tests/detect/yaml"))
+        .build();
+
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .ends_with("tests/detect/yaml\" is not of a registered file format"));
+
+    // A format given explicitly is not detected
+    let c = Config::builder()
+        .add_source(File::new("tests/detect/yaml", FileFormat::Yaml).detect_format(true))
+        .build()
+        .unwrap();
+    assert_eq!(c.get_int("server.port").unwrap(), 8080);
+}
+
+#[test]
+fn test_file_detect_format_error() {
+    let res = Config::builder()
+        .add_source(File::with_name("tests/detect/broken").detect_format(true))
+        .build();
+
+    assert!(res.unwrap_err().to_string().ends_with(
+        "tests/detect/broken\" is not of a registered file format, \
+         nor could one be detected from its content"
+    ));
+}
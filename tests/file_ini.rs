@@ -4,7 +4,7 @@ use serde_derive::Deserialize;
 
 use std::path::PathBuf;
 
-use config::{Config, File, FileFormat};
+use config::{Config, File, FileFormat, IniFormat, Source, ValueKind};
 
 #[derive(Debug, Deserialize, PartialEq)]
 struct Place {
@@ -169,3 +169,105 @@ fn test_override_lowercase_value_for_enums() {
         EnumSettings::Bar("I have been overridden_with_lower_case".to_string())
     );
 }
+
+#[test]
+fn test_typed_arrays() {
+    let format = IniFormat::new()
+        .try_parsing(true)
+        .arrays(true)
+        .nested_sections(true);
+
+    let c = Config::builder()
+        .add_source(File::new("tests/Settings-nested", format))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    assert_eq!(values["debug"].kind, ValueKind::Boolean(true));
+    if cfg!(feature = "arbitrary_precision") {
+        assert_eq!(values["ratio"].kind, ValueKind::Decimal("0.5".into()));
+    } else {
+        assert_eq!(values["ratio"].kind, ValueKind::Float(0.5));
+    }
+    assert_eq!(values["version"].kind, ValueKind::String("007x".into()));
+
+    assert_eq!(c.get_string("server.host").unwrap(), "example.com");
+    assert_eq!(c.get_int("server.port").unwrap(), 8080);
+    assert_eq!(c.get_int("server.workers").unwrap(), 4);
+    assert!(c.get_bool("server.tls.enabled").unwrap());
+    assert_eq!(
+        c.get_string("server.tls.cert").unwrap(),
+        "/etc/ssl/cert.pem"
+    );
+
+    assert_eq!(
+        c.get::<Vec<String>>("upstream.backend").unwrap(),
+        ["one", "two"]
+    );
+    assert_eq!(c.get::<Vec<u32>>("upstream.weights").unwrap(), [3]);
+}
+
+#[test]
+fn test_default_untyped() {
+    let c = Config::builder()
+        .add_source(File::new("tests/Settings-nested", FileFormat::Ini))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    assert_eq!(values["debug"].kind, ValueKind::String("true".into()));
+
+    // A section given again replaces the earlier one
+    assert!(c.get_string("server.host").is_err());
+    assert_eq!(c.get_string("server.workers").unwrap(), "4");
+    assert_eq!(c.get_string("server.tls.enabled").unwrap(), "TRUE");
+
+    let upstream = c.get_table("upstream").unwrap();
+    assert_eq!(upstream["backend"].clone().into_string().unwrap(), "two");
+    assert!(upstream.contains_key("weights[]"));
+}
+
+#[test]
+fn test_error_section_conflict() {
+    let res = Config::builder()
+        .add_source(File::from_str(
+            "[server]\ntls = off\n[server.tls]\nenabled = true",
+            IniFormat::new().nested_sections(true),
+        ))
+        .build();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "INI section `server.tls` conflicts with an option of the same name"
+    );
+}
+
+#[test]
+fn test_error_option_conflict() {
+    for format in [
+        IniFormat::new().nested_sections(true),
+        IniFormat::new().nested_sections(true).arrays(true),
+    ] {
+        let res = Config::builder()
+            .add_source(File::from_str(
+                "[server.tls]\nenabled = true\n[server]\ntls = off",
+                format,
+            ))
+            .build();
+
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "INI option `tls` in section `server` conflicts with a section of the same name"
+        );
+    }
+}
+
+#[test]
+fn test_default_section_replaces_option() {
+    let c = Config::builder()
+        .add_source(File::from_str("a = 1\n[a]\nb = 2", FileFormat::Ini))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("a.b").unwrap(), "2");
+}
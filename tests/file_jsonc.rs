@@ -0,0 +1,156 @@
+#![cfg(feature = "json")]
+
+use serde_derive::Deserialize;
+
+use std::path::PathBuf;
+
+use config::{Config, File, FileFormat, Map, Value};
+use float_cmp::ApproxEqUlps;
+
+#[derive(Debug, Deserialize)]
+struct Place {
+    name: String,
+    longitude: f64,
+    latitude: f64,
+    favorite: bool,
+    telephone: Option<String>,
+    reviews: u64,
+    creator: Map<String, Value>,
+    rating: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Settings {
+    debug: f64,
+    production: Option<String>,
+    place: Place,
+    #[serde(rename = "arr")]
+    elements: Vec<String>,
+    url: String,
+}
+
+fn make() -> Config {
+    Config::builder()
+        .add_source(File::new("tests/Settings", FileFormat::Jsonc))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_file() {
+    let c = make();
+
+    // Deserialize the entire file as single struct
+    let s: Settings = c.try_deserialize().unwrap();
+
+    assert!(s.debug.approx_eq_ulps(&1.0, 2));
+    assert_eq!(s.production, Some("false".to_string()));
+    assert_eq!(s.place.name, "Torre di Pisa");
+    assert!(s.place.longitude.approx_eq_ulps(&43.722_498_5, 2));
+    assert!(s.place.latitude.approx_eq_ulps(&10.397_052_2, 2));
+    assert!(!s.place.favorite);
+    assert_eq!(s.place.reviews, 3866);
+    assert_eq!(s.place.rating, Some(4.5));
+    assert_eq!(s.place.telephone, None);
+    assert_eq!(s.elements.len(), 10);
+    assert_eq!(s.elements[3], "4".to_string());
+    assert_eq!(s.url, "https://example.com/*not-a-comment*/");
+    assert_eq!(
+        s.place.creator["email"].clone().into_string().unwrap(),
+        "jsmith@localhost".to_string()
+    );
+}
+
+#[test]
+fn test_file_auto() {
+    let c = Config::builder()
+        .add_source(File::with_name("tests/Settings.jsonc"))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("debug_jsonc").ok(), Some(true));
+}
+
+#[test]
+fn test_error_parse() {
+    let res = Config::builder()
+        .add_source(File::new("tests/Settings-invalid", FileFormat::Jsonc))
+        .build();
+
+    let path_with_extension: PathBuf = ["tests", "Settings-invalid.jsonc"].iter().collect();
+
+    // The reported position still matches the original, commented text
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        format!(
+            "key must be a string at line 3 column 3 in {}",
+            path_with_extension.display()
+        )
+    );
+}
+
+#[test]
+fn test_strings_are_untouched() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            {
+              "line": "// not a comment",
+              "block": "/* not a comment */",
+              "quoted": "a \"quoted, \" ] string", // a comment with a "quote"
+              "list": ["a,", "]",],
+            }
+            "#,
+            FileFormat::Jsonc,
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("line").unwrap(), "// not a comment");
+    assert_eq!(c.get_string("block").unwrap(), "/* not a comment */");
+    assert_eq!(c.get_string("quoted").unwrap(), "a \"quoted, \" ] string");
+    assert_eq!(
+        c.get::<Vec<String>>("list").unwrap(),
+        vec!["a,".to_string(), "]".to_string()]
+    );
+}
+
+#[test]
+fn test_json_rejects_comments() {
+    let res = Config::builder()
+        .add_source(File::from_str(
+            r#"{ "a": 1 // comment
+            }"#,
+            FileFormat::Json,
+        ))
+        .build();
+
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_error_comma_without_value() {
+    for text in &[r#"{"a": [,]}"#, r#"{"a": {,}}"#, r#"{"a": [1,,]}"#, "{,}"] {
+        let res = Config::builder()
+            .add_source(File::from_str(text, FileFormat::Jsonc))
+            .build();
+
+        assert!(res.is_err(), "{} was accepted", text);
+    }
+}
+
+#[test]
+fn test_error_unterminated_comment() {
+    let res = Config::builder()
+        .add_source(File::from_str(
+            "{\n  \"a\": 1\n} /* never closed",
+            FileFormat::Jsonc,
+        ))
+        .build();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "unterminated block comment at line 3 column 3"
+    );
+}
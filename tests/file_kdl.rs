@@ -0,0 +1,257 @@
+#![cfg(feature = "kdl")]
+
+use serde_derive::Deserialize;
+
+use config::{Config, File, FileFormat, Map, Value};
+use float_cmp::ApproxEqUlps;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+struct Place {
+    name: String,
+    longitude: f64,
+    latitude: f64,
+    favorite: bool,
+    telephone: Option<String>,
+    reviews: u64,
+    creator: Map<String, Value>,
+    rating: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Settings {
+    debug: f64,
+    production: Option<String>,
+    place: Place,
+    #[serde(rename = "arr")]
+    elements: Vec<String>,
+}
+
+fn make() -> Config {
+    Config::builder()
+        .add_source(File::new("tests/Settings", FileFormat::Kdl))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_file() {
+    let c = make();
+
+    // Deserialize the entire file as single struct
+    let s: Settings = c.try_deserialize().unwrap();
+
+    assert!(s.debug.approx_eq_ulps(&1.0, 2));
+    assert_eq!(s.production, Some("false".to_string()));
+    assert_eq!(s.place.name, "Torre di Pisa");
+    assert!(s.place.longitude.approx_eq_ulps(&43.722_498_5, 2));
+    assert!(s.place.latitude.approx_eq_ulps(&10.397_052_2, 2));
+    assert!(!s.place.favorite);
+    assert_eq!(s.place.reviews, 3866);
+    assert_eq!(s.place.rating, Some(4.5));
+    assert_eq!(s.place.telephone, None);
+    assert_eq!(s.elements.len(), 10);
+    assert_eq!(s.elements[3], "4".to_string());
+    if cfg!(feature = "preserve_order") {
+        assert_eq!(
+            s.place
+                .creator
+                .into_iter()
+                .collect::<Vec<(String, config::Value)>>(),
+            vec![
+                ("name".to_string(), "John Smith".into()),
+                ("username".into(), "jsmith".into()),
+                ("email".into(), "jsmith@localhost".into()),
+            ]
+        );
+    } else {
+        assert_eq!(
+            s.place.creator["name"].clone().into_string().unwrap(),
+            "John Smith".to_string()
+        );
+    }
+}
+
+#[test]
+fn test_file_auto() {
+    let c = Config::builder()
+        .add_source(File::with_name("tests/Settings.kdl"))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("debug_kdl").ok(), Some(true));
+    assert_eq!(
+        c.get("place.creator.username").ok(),
+        Some("jsmith".to_string())
+    );
+}
+
+#[test]
+fn test_error_parse() {
+    let res = Config::builder()
+        .add_source(File::new("tests/Settings-invalid", FileFormat::Kdl))
+        .build();
+
+    let path_with_extension: PathBuf = ["tests", "Settings-invalid.kdl"].iter().collect();
+
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        format!(
+            "No closing '}}' for child block at line 2 column 7 in {}",
+            path_with_extension.display()
+        )
+    );
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Cluster {
+    servers: Vec<Server>,
+    tags: Vec<String>,
+    backup: Vec<Server>,
+}
+
+#[test]
+fn test_lists() {
+    let cluster: Cluster = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            servers {
+                - host=alpha port=8080
+                - {
+                    host beta
+                    port 8081
+                }
+            }
+            tags {
+                - one
+                - two
+            }
+            backup host=gamma port=9000
+            backup host=delta port=9001
+            "#,
+            FileFormat::Kdl,
+        ))
+        .build()
+        .unwrap()
+        .try_deserialize()
+        .unwrap();
+
+    assert_eq!(
+        cluster,
+        Cluster {
+            servers: vec![
+                Server {
+                    host: "alpha".to_string(),
+                    port: 8080,
+                },
+                Server {
+                    host: "beta".to_string(),
+                    port: 8081,
+                },
+            ],
+            tags: vec!["one".to_string(), "two".to_string()],
+            backup: vec![
+                Server {
+                    host: "gamma".to_string(),
+                    port: 9000,
+                },
+                Server {
+                    host: "delta".to_string(),
+                    port: 9001,
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_properties_and_children() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            database url="postgres://localhost" pool=4 {
+                pool 8
+                timeout 30
+            }
+            empty
+            "#,
+            FileFormat::Kdl,
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        c.get_string("database.url").unwrap(),
+        "postgres://localhost"
+    );
+    assert_eq!(c.get_int("database.pool").unwrap(), 8);
+    assert_eq!(c.get_int("database.timeout").unwrap(), 30);
+    assert_eq!(c.get::<Option<String>>("empty").unwrap(), None);
+}
+
+#[test]
+fn test_error_mixed_entries() {
+    let res = Config::builder()
+        .add_source(File::from_str(r#"server "main" port=80"#, FileFormat::Kdl))
+        .build();
+
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "KDL node `server` mixes arguments with properties or children"
+    );
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum EnumSettings {
+    Bar(String),
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct StructSettings {
+    foo: String,
+    bar: String,
+}
+
+#[test]
+fn test_override_lowercase_value_for_struct() {
+    std::env::set_var("config_foo", "I have been overridden_with_lower_case");
+
+    let cfg = Config::builder()
+        .add_source(File::new("tests/Settings", FileFormat::Kdl))
+        .add_source(config::Environment::with_prefix("config").separator("_"))
+        .build()
+        .unwrap();
+
+    let values: StructSettings = cfg.try_deserialize().unwrap();
+    assert_eq!(
+        values.foo,
+        "I have been overridden_with_lower_case".to_string()
+    );
+    assert_ne!(values.foo, "I am bar".to_string());
+}
+
+#[test]
+fn test_override_lowercase_value_for_enums() {
+    std::env::set_var("test_bar", "I have been overridden_with_lower_case");
+
+    let cfg = Config::builder()
+        .add_source(File::new("tests/Settings-enum-test", FileFormat::Kdl))
+        .add_source(config::Environment::with_prefix("test").separator("_"))
+        .build()
+        .unwrap();
+
+    let param: EnumSettings = cfg.try_deserialize().unwrap();
+
+    assert_eq!(
+        param,
+        EnumSettings::Bar("I have been overridden_with_lower_case".to_string())
+    );
+}
@@ -0,0 +1,123 @@
+#![cfg(all(feature = "toml", feature = "json"))]
+
+use std::io::{self, Read};
+
+use config::{
+    Config, File, FileFormat, FileSource, FileSourceBytes, FileSourceReader, FileSourceString,
+    FileStoredFormat,
+};
+
+fn utf16(text: &str, bom: [u8; 2], to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+    let mut bytes = bom.to_vec();
+    bytes.extend(text.encode_utf16().flat_map(to_bytes));
+    bytes
+}
+
+#[test]
+fn test_file_from_bytes() {
+    let text = "[server]\nport = 8080\n";
+    let mut utf8 = vec![0xef, 0xbb, 0xbf];
+    utf8.extend_from_slice(text.as_bytes());
+
+    for bytes in [
+        text.as_bytes().to_vec(),
+        utf8,
+        utf16(text, [0xff, 0xfe], u16::to_le_bytes),
+        utf16(text, [0xfe, 0xff], u16::to_be_bytes),
+    ] {
+        let c = Config::builder()
+            .add_source(File::from_bytes("embedded.toml", bytes, FileFormat::Toml))
+            .build()
+            .unwrap();
+
+        assert_eq!(c.get_int("server.port").unwrap(), 8080);
+    }
+}
+
+#[test]
+fn test_file_from_bytes_detect_format() {
+    let file = File::from(FileSourceBytes::new("embedded", r#"{"port": 8080}"#));
+
+    assert_eq!(
+        file.resolve()
+            .unwrap()
+            .detected_format()
+            .map(|format| format.file_extensions()),
+        Some(FileFormat::Json.file_extensions())
+    );
+    let c = Config::builder().add_source(file).build().unwrap();
+    assert_eq!(c.get_int("port").unwrap(), 8080);
+}
+
+#[test]
+fn test_file_from_bytes_error() {
+    let res = Config::builder()
+        .add_source(File::from_bytes(
+            "embedded.toml",
+            vec![0xff, 0xfe, 0x00],
+            FileFormat::Toml,
+        ))
+        .build();
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "configuration file \"embedded.toml\" is not valid text: UTF-16 text has an odd number of bytes"
+    );
+
+    let res = Config::builder()
+        .add_source(File::from(FileSourceBytes::new("embedded", "[broken")))
+        .build();
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "configuration file \"embedded\" has no format set, nor could one be detected from its content"
+    );
+
+    let res = FileSource::<FileFormat>::resolve(&FileSourceString::from("[broken"), None);
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        "configuration string has no format set, nor could one be detected from its content"
+    );
+}
+
+#[test]
+fn test_file_from_reader() {
+    let file = File::from_reader(
+        "settings.json",
+        io::Cursor::new(r#"{"port": 8080}"#),
+        FileFormat::Json,
+    );
+
+    // The reader is read once, and what was read is used again
+    for _ in 0..2 {
+        let c = Config::builder().add_source(file.clone()).build().unwrap();
+        assert_eq!(c.get_int("port").unwrap(), 8080);
+    }
+
+    let file = File::from(FileSourceReader::new("-", io::Cursor::new("port = 8080")));
+    assert_eq!(
+        file.resolve()
+            .unwrap()
+            .detected_format()
+            .map(|format| format.file_extensions()),
+        Some(FileFormat::Toml.file_extensions())
+    );
+}
+
+#[test]
+fn test_file_from_reader_error() {
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "pipe closed"))
+        }
+    }
+
+    let file = File::from_reader("-", FailingReader, FileFormat::Json);
+    for _ in 0..2 {
+        let res = Config::builder().add_source(file.clone()).build();
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "could not read configuration file \"-\": pipe closed"
+        );
+    }
+}
@@ -4,7 +4,7 @@ use serde_derive::Deserialize;
 
 use std::path::PathBuf;
 
-use config::{Config, File, FileFormat, Map, Value};
+use config::{Config, File, FileFormat, Map, Value, YamlDocuments, YamlFormat};
 use float_cmp::ApproxEqUlps;
 
 #[derive(Debug, Deserialize)]
@@ -120,6 +120,350 @@ fn test_yaml_parsing_key() {
     );
 }
 
+const DOCUMENTS: &str = r#"
+a: 1
+nested:
+  b: 2
+  c: 3
+---
+a: 10
+nested:
+  c: 30
+---
+a: 100
+"#;
+
+#[test]
+fn test_multiple_documents_error() {
+    let res = Config::builder()
+        .add_source(File::from_str(DOCUMENTS, FileFormat::Yaml))
+        .build();
+
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "Got 3 YAML documents, expected 1"
+    );
+}
+
+#[test]
+fn test_multiple_documents_merge() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            DOCUMENTS,
+            YamlFormat::new().documents(YamlDocuments::Merge),
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_int("a").unwrap(), 100);
+    assert_eq!(c.get_int("nested.b").unwrap(), 2);
+    assert_eq!(c.get_int("nested.c").unwrap(), 30);
+}
+
+#[test]
+fn test_multiple_documents_index() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            DOCUMENTS,
+            YamlFormat::new().documents(YamlDocuments::Index(1)),
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_int("a").unwrap(), 10);
+    assert!(c.get_int("nested.b").is_err());
+
+    let res = Config::builder()
+        .add_source(File::from_str(
+            DOCUMENTS,
+            YamlFormat::new().documents(YamlDocuments::Index(3)),
+        ))
+        .build();
+
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "YAML document 3 requested, but only 3 found"
+    );
+}
+
+#[test]
+fn test_multiple_documents_select() {
+    let profile = |value: &str| {
+        Config::builder()
+            .add_source(File::new(
+                "tests/Settings-profiles",
+                YamlFormat::new().documents(YamlDocuments::Select {
+                    key: "profile".into(),
+                    value: value.into(),
+                }),
+            ))
+            .build()
+            .unwrap()
+    };
+
+    let c = profile("production");
+    assert_eq!(c.get_string("server.host").unwrap(), "example.com");
+    assert_eq!(c.get_int("server.port").unwrap(), 8080);
+    assert_eq!(c.get_string("log").unwrap(), "warn");
+
+    let c = profile("staging");
+    assert_eq!(c.get_string("server.host").unwrap(), "staging.example.com");
+    assert_eq!(c.get_string("log").unwrap(), "debug");
+
+    let c = profile("development");
+    assert_eq!(c.get_string("server.host").unwrap(), "localhost");
+    assert!(c.get_string("profile").is_err());
+}
+
+#[test]
+fn test_anchors_and_merge_keys() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+defaults: &defaults
+  adapter: postgres
+  host: localhost
+  pool: 5
+labels: &labels
+  team: core
+  tier: backend
+ports: &ports [80, 443]
+
+development:
+  <<: *defaults
+  database: dev
+  pool: 2
+production:
+  <<: [*labels, *defaults, {tier: frontend, region: eu}]
+  database: prod
+  ports: *ports
+"#,
+            FileFormat::Yaml,
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("development.adapter").unwrap(), "postgres");
+    assert_eq!(c.get_string("development.database").unwrap(), "dev");
+    assert_eq!(c.get_int("development.pool").unwrap(), 2);
+    assert!(c.get_string("development.<<").is_err());
+
+    assert_eq!(c.get_string("production.host").unwrap(), "localhost");
+    assert_eq!(c.get_int("production.pool").unwrap(), 5);
+    assert_eq!(c.get_string("production.tier").unwrap(), "backend");
+    assert_eq!(c.get_string("production.region").unwrap(), "eu");
+    assert_eq!(
+        c.get::<Vec<u16>>("production.ports").unwrap(),
+        vec![80, 443]
+    );
+}
+
+#[test]
+fn test_nested_merge_keys() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+base: &base
+  a: 1
+middle: &middle
+  <<: *base
+  b: 2
+top:
+  <<: *middle
+  c: 3
+"#,
+            FileFormat::Yaml,
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_int("top.a").unwrap(), 1);
+    assert_eq!(c.get_int("top.b").unwrap(), 2);
+    assert_eq!(c.get_int("top.c").unwrap(), 3);
+}
+
+#[test]
+fn test_quoted_merge_key() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+base: &base
+  a: 1
+quoted:
+  "<<": x
+tagged:
+  !!str <<: *base
+plain:
+  key: <<
+"#,
+            FileFormat::Yaml,
+        ))
+        .build()
+        .unwrap();
+
+    let quoted = c.get_table("quoted").unwrap();
+    assert_eq!(quoted.len(), 1);
+    assert_eq!(quoted["<<"].clone().into_string().unwrap(), "x");
+
+    let tagged = c.get_table("tagged").unwrap();
+    assert_eq!(tagged.len(), 1);
+    assert!(tagged["<<"].clone().into_table().is_ok());
+
+    assert_eq!(c.get_string("plain.key").unwrap(), "<<");
+}
+
+#[test]
+fn test_error_merge_key() {
+    let res = Config::builder()
+        .add_source(File::from_str(
+            r#"
+list: &list [1, 2]
+map:
+  <<: *list
+"#,
+            FileFormat::Yaml,
+        ))
+        .build();
+
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "YAML merge key `<<` expects a mapping or a sequence of mappings"
+    );
+}
+
+#[test]
+fn test_error_dangling_alias() {
+    let res = Config::builder()
+        .add_source(File::from_str("a: *missing", FileFormat::Yaml))
+        .build();
+
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "while parsing node, found unknown anchor at byte 3 line 1 column 4"
+    );
+
+    let res = Config::builder()
+        .add_source(File::from_str("a: &self [1, *self]", FileFormat::Yaml))
+        .build();
+
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "YAML alias refers to an undefined anchor, or a value does not match its tag"
+    );
+}
+
+#[test]
+fn test_builtin_tags() {
+    std::env::set_var("CONFIG_TAGS_USER", "jsmith");
+
+    let c = Config::builder()
+        .add_source(File::new(
+            "tests/Settings-tags.yaml",
+            YamlFormat::new().with_builtin_tags(),
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("user").unwrap(), "jsmith");
+    assert_eq!(c.get_string("home").unwrap(), "/home/config");
+    assert_eq!(c.get_string("password").unwrap(), "s3cr3t");
+    assert_eq!(c.get_string("database.host").unwrap(), "localhost");
+    assert_eq!(c.get_int("database.port").unwrap(), 5432);
+}
+
+#[test]
+fn test_tags_disabled() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            "user: !env CONFIG_TAGS_USER\nport: !custom 8080",
+            FileFormat::Yaml,
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("user").unwrap(), "CONFIG_TAGS_USER");
+    assert_eq!(c.get_string("port").unwrap(), "8080");
+}
+
+#[test]
+fn test_custom_tag() {
+    let format = YamlFormat::new().with_tag("!upper", |value| {
+        Ok(Value::from(value.into_string()?.to_uppercase()))
+    });
+
+    let c = Config::builder()
+        .add_source(File::from_str(
+            "name: !upper jsmith\nlist: !upper [a, b]",
+            format.clone(),
+        ))
+        .build();
+
+    assert_eq!(
+        c.unwrap_err().to_string(),
+        "invalid type: sequence, expected a string"
+    );
+
+    let c = Config::builder()
+        .add_source(File::from_str("name: !upper jsmith", format))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_string("name").unwrap(), "JSMITH");
+}
+
+#[test]
+fn test_error_tags() {
+    let format = YamlFormat::new().with_builtin_tags();
+
+    let res = Config::builder()
+        .add_source(File::from_str(
+            "user: !env CONFIG_TAGS_MISSING",
+            format.clone(),
+        ))
+        .build();
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "environment variable `CONFIG_TAGS_MISSING` is not set"
+    );
+
+    let res = Config::builder()
+        .add_source(File::from_str("home: !env_or [HOME]", format.clone()))
+        .build();
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "YAML tag `!env_or` expects a variable name and a default value"
+    );
+
+    let res = Config::builder()
+        .add_source(File::new("tests/Settings-tags-cycle.yaml", format))
+        .build();
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "`!include` of tests/Settings-tags-cycle.yaml forms a cycle in \
+         tests/Settings-tags-cycle.yaml"
+    );
+}
+
+#[test]
+fn test_error_include_cycle_spelled_differently() {
+    let format = YamlFormat::new().with_builtin_tags();
+
+    let res = Config::builder()
+        .add_source(File::new("tests/include-cycle/a.yaml", format))
+        .build();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "`!include` of tests/include-cycle/../include-cycle/a.yaml forms a cycle in \
+         tests/include-cycle/./b.yaml in tests/include-cycle/a.yaml"
+    );
+}
+
 #[derive(Debug, Deserialize, PartialEq)]
 enum EnumSettings {
     Bar(String),
@@ -224,3 +568,66 @@ fn test_override_lowercase_value_for_enums() {
         EnumSettings::Bar("I have been overridden_with_lower_case".to_string())
     );
 }
+
+#[test]
+fn test_scalar_keys() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+flags:
+  true: on
+  false: off
+  ~: nothing
+  1: one
+  2001-12-14: date
+"#,
+            FileFormat::Yaml,
+        ))
+        .build()
+        .unwrap();
+
+    let flags = c.get_table("flags").unwrap();
+    let mut keys: Vec<_> = flags.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["1", "2001-12-14", "false", "null", "true"]);
+    assert_eq!(flags["true"].clone().into_string().unwrap(), "on");
+    assert_eq!(flags["null"].clone().into_string().unwrap(), "nothing");
+    assert_eq!(c.get_string("flags.1").unwrap(), "one");
+}
+
+#[test]
+fn test_error_float_key() {
+    for (text, key) in [("1.5: y\n1.0: w", "1.5"), ("flags:\n  2.5: w", "2.5")] {
+        let res = Config::builder()
+            .add_source(File::from_str(text, FileFormat::Yaml))
+            .build();
+
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            format!(
+                "YAML mapping key `{}` is a float, which cannot be a key as its `.` separates keys",
+                key
+            )
+        );
+    }
+}
+
+#[test]
+fn test_error_complex_key() {
+    for (text, found) in [
+        ("? [a, b]\n: value", "sequence"),
+        ("? {a: b}\n: value", "mapping"),
+    ] {
+        let res = Config::builder()
+            .add_source(File::from_str(text, FileFormat::Yaml))
+            .build();
+
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            format!(
+                "YAML mapping keys must be scalars, found a {} as key",
+                found
+            )
+        );
+    }
+}
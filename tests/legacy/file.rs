@@ -16,10 +16,10 @@ fn test_file_required_not_found() {
     let res = c.merge(File::new("tests/NoSettings", FileFormat::Yaml));
 
     assert!(res.is_err());
-    assert_eq!(
-        res.unwrap_err().to_string(),
-        "configuration file \"tests/NoSettings\" not found".to_string()
-    );
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("configuration file \"tests/NoSettings\" not found, tried "));
 }
 
 #[test]
@@ -38,10 +38,10 @@ fn test_file_auto_not_found() {
     let res = c.merge(File::with_name("tests/NoSettings"));
 
     assert!(res.is_err());
-    assert_eq!(
-        res.unwrap_err().to_string(),
-        "configuration file \"tests/NoSettings\" not found".to_string()
-    );
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("configuration file \"tests/NoSettings\" not found, tried "));
 }
 
 #[test]
@@ -1,2 +1,5 @@
+// The legacy datetime tests are gated on the long gone `hjson` feature
+#![allow(unknown_lints, unexpected_cfgs)]
+
 #[allow(deprecated)]
 pub mod legacy;
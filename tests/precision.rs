@@ -0,0 +1,222 @@
+use config::{Config, File, FileFormat, Source, ValueKind};
+use serde_derive::{Deserialize, Serialize};
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_wide_integers() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            {
+                "small": -1,
+                "unsigned": 18446744073709551615,
+                "ratio": 0.5
+            }
+            "#,
+            FileFormat::Json,
+        ))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    assert_eq!(values["small"].kind, ValueKind::I64(-1));
+    assert_eq!(values["unsigned"].kind, ValueKind::U64(u64::MAX));
+    assert_eq!(c.get::<u64>("unsigned").unwrap(), u64::MAX);
+
+    if cfg!(feature = "arbitrary_precision") {
+        assert_eq!(values["ratio"].kind, ValueKind::Decimal("0.5".into()));
+    } else {
+        assert_eq!(values["ratio"].kind, ValueKind::Float(0.5));
+    }
+}
+
+#[cfg(all(feature = "json", feature = "arbitrary_precision"))]
+#[test]
+fn test_json_arbitrary_precision() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            {
+                "price": 10.10,
+                "precise": 0.1000000000000000055511151231257827,
+                "huge": 340282366920938463463374607431768211455,
+                "negative": -170141183460469231731687303715884105728
+            }
+            "#,
+            FileFormat::Json,
+        ))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    assert_eq!(values["price"].kind, ValueKind::Decimal("10.10".into()));
+    assert_eq!(c.get_string("price").unwrap(), "10.10");
+    assert_eq!(
+        c.get_string("precise").unwrap(),
+        "0.1000000000000000055511151231257827"
+    );
+    assert_eq!(c.get_float("price").unwrap(), 10.1);
+    assert_eq!(c.get_int("price").unwrap(), 10);
+    assert_eq!(values["huge"].kind, ValueKind::U128(u128::MAX));
+    assert_eq!(values["negative"].kind, ValueKind::I128(i128::MIN));
+}
+
+#[cfg(all(feature = "json", feature = "arbitrary_precision"))]
+#[test]
+fn test_json_decimal_deserialize_any() {
+    #[derive(Debug, Deserialize)]
+    #[serde(tag = "kind")]
+    enum Tagged {
+        Price { price: f64 },
+    }
+
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"{ "kind": "Price", "price": 10.10 }"#,
+            FileFormat::Json,
+        ))
+        .build()
+        .unwrap();
+
+    let Tagged::Price { price } = c.clone().try_deserialize().unwrap();
+    assert_eq!(price, 10.1);
+
+    let json: serde_json::Value = c.try_deserialize().unwrap();
+    assert_eq!(json["price"], serde_json::json!(10.1));
+}
+
+#[cfg(feature = "json5")]
+#[test]
+fn test_json5_wide_integers() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            {
+                // 18446744073709551615
+                unsigned: 18446744073709551615,
+                wide: -170141183460469231731687303715884105728,
+                'text': "1e400",
+                hex: 0xFF,
+                Infinity: -Infinity,
+                ratio: 0.5,
+            }
+            "#,
+            FileFormat::Json5,
+        ))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    assert_eq!(values["unsigned"].kind, ValueKind::U64(u64::MAX));
+    assert_eq!(values["wide"].kind, ValueKind::I128(i128::MIN));
+    assert_eq!(values["text"].kind, ValueKind::String("1e400".into()));
+    assert_eq!(values["hex"].kind, ValueKind::I64(255));
+    assert_eq!(values["infinity"].kind, ValueKind::Float(f64::NEG_INFINITY));
+    assert_eq!(c.get::<u64>("unsigned").unwrap(), u64::MAX);
+
+    if cfg!(feature = "arbitrary_precision") {
+        assert_eq!(values["ratio"].kind, ValueKind::Decimal("0.5".into()));
+    } else {
+        assert_eq!(values["ratio"].kind, ValueKind::Float(0.5));
+    }
+}
+
+#[cfg(feature = "json5")]
+#[test]
+fn test_json5_wide_integers_nested() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            {
+                /* 1, 2, "3" */ "quoted": [1, [18446744073709551615, /* 0x10 */ [-2]], 3.5],
+                'Infinity': { NaN: 'NaN', inner: [0x10, 170141183460469231731687303715884105727] },
+                // 99999999999999999999
+                last: [[[-9223372036854775809]], 'a // b', "c /* d", 7],
+            }
+            "#,
+            FileFormat::Json5,
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_int("quoted[0]").unwrap(), 1);
+    assert_eq!(c.get::<u64>("quoted[1][0]").unwrap(), u64::MAX);
+    assert_eq!(c.get_int("quoted[1][1][0]").unwrap(), -2);
+    assert_eq!(c.get_float("quoted[2]").unwrap(), 3.5);
+    assert_eq!(c.get_string("infinity.nan").unwrap(), "NaN");
+    assert_eq!(c.get_int("infinity.inner[0]").unwrap(), 16);
+    assert_eq!(c.get::<i128>("infinity.inner[1]").unwrap(), i128::MAX);
+    assert_eq!(
+        c.get::<i128>("last[0][0][0]").unwrap(),
+        i128::from(i64::MIN) - 1
+    );
+    assert_eq!(c.get_string("last[1]").unwrap(), "a // b");
+    assert_eq!(c.get_string("last[2]").unwrap(), "c /* d");
+    assert_eq!(c.get_int("last[3]").unwrap(), 7);
+}
+
+#[cfg(feature = "json5")]
+#[test]
+fn test_json5_error_integer_too_wide() {
+    let res = Config::builder()
+        .add_source(File::from_str(
+            "{ list: [1, 340282366920938463463374607431768211456] }",
+            FileFormat::Json5,
+        ))
+        .build();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "JSON5 integer `340282366920938463463374607431768211456` does not fit in 128 bits"
+    );
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_wide_integers() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            "
+            unsigned: 18446744073709551615
+            wide: -170141183460469231731687303715884105728
+            ",
+            FileFormat::Yaml,
+        ))
+        .build()
+        .unwrap();
+
+    let values = c.collect().unwrap();
+    assert_eq!(values["unsigned"].kind, ValueKind::U64(u64::MAX));
+    assert_eq!(values["wide"].kind, ValueKind::I128(i128::MIN));
+}
+
+#[test]
+fn test_env_wide_integers() {
+    temp_env::with_var("PRECISION_UNSIGNED", Some("18446744073709551615"), || {
+        let values = config::Environment::with_prefix("PRECISION")
+            .try_parsing(true)
+            .collect()
+            .unwrap();
+
+        assert_eq!(values["unsigned"].kind, ValueKind::U64(u64::MAX));
+    });
+}
+
+#[test]
+fn test_serialize_wide_integers() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Wide {
+        unsigned: u64,
+        wide: i128,
+        widest: u128,
+    }
+
+    let wide = Wide {
+        unsigned: u64::MAX,
+        wide: i128::MIN,
+        widest: u128::MAX,
+    };
+
+    let c = Config::try_from(&wide).unwrap();
+    assert_eq!(c.try_deserialize::<Wide>().unwrap(), wide);
+}
@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use config::{ByteSize, Config, Value};
+use serde_derive::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Settings {
+    timeout: Duration,
+    retry: Option<Duration>,
+    cache: ByteSize,
+}
+
+#[derive(Debug, Deserialize)]
+struct Root {
+    #[allow(dead_code)]
+    settings: Settings,
+}
+
+fn make(timeout: &str, cache: &str) -> Config {
+    Config::builder()
+        .set_override("settings.timeout", timeout)
+        .unwrap()
+        .set_override("settings.cache", cache)
+        .unwrap()
+        .set_override("settings.retry", 5)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_deserialize() {
+    let c = make("1h30m", "512MiB");
+    let s: Settings = c.get("settings").unwrap();
+
+    assert_eq!(s.timeout, Duration::from_secs(90 * 60));
+    assert_eq!(s.retry, Some(Duration::from_secs(5)));
+    assert_eq!(s.cache, ByteSize(512 * 1024 * 1024));
+}
+
+#[test]
+fn test_deserialize_legacy_duration_table() {
+    let c = Config::builder()
+        .set_override("timeout.secs", 2)
+        .unwrap()
+        .set_override("timeout.nanos", 500)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get::<Duration>("timeout").unwrap(), Duration::new(2, 500));
+}
+
+#[test]
+fn test_duration_formats() {
+    for (text, expected) in [
+        ("30", Duration::from_secs(30)),
+        ("1.5", Duration::from_millis(1500)),
+        (".25", Duration::from_millis(250)),
+        ("30s", Duration::from_secs(30)),
+        ("1.5s", Duration::from_millis(1500)),
+        ("250ms", Duration::from_millis(250)),
+        ("1h 30m 15s", Duration::from_secs(5415)),
+        ("2d", Duration::from_secs(2 * 86_400)),
+        ("10us", Duration::from_micros(10)),
+        ("7ns", Duration::from_nanos(7)),
+    ] {
+        assert_eq!(
+            Value::from(text).into_duration().unwrap(),
+            expected,
+            "{}",
+            text
+        );
+    }
+
+    assert_eq!(
+        Value::from(2.5).into_duration().unwrap(),
+        Duration::from_millis(2500)
+    );
+
+    let c = Config::builder()
+        .set_override("t", "1.5")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(c.get::<Duration>("t").unwrap(), Duration::from_millis(1500));
+
+    for text in ["", ".", "1.2.3", "1h30", "5 parsecs", "s", "1..5s"] {
+        assert!(Value::from(text).into_duration().is_err(), "{}", text);
+    }
+    // The fraction pushes the total past what a u128 of nanoseconds holds
+    let err = Value::from("340282366920938463463374607431768211.999us")
+        .into_duration()
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: string \"340282366920938463463374607431768211.999us\", expected a duration"
+    );
+}
+
+#[test]
+fn test_byte_size_formats() {
+    for (text, expected) in [
+        ("1024", 1024),
+        ("1 B", 1),
+        ("1kB", 1000),
+        ("1kib", 1024),
+        ("1.5 GiB", 3 << 29),
+        ("2MB", 2_000_000),
+    ] {
+        assert_eq!(
+            Value::from(text).into_byte_size().unwrap(),
+            expected,
+            "{}",
+            text
+        );
+    }
+
+    assert_eq!(ByteSize(512 * 1024 * 1024).to_string(), "512MiB");
+    assert_eq!(ByteSize(3_000).to_string(), "3kB");
+    assert_eq!(ByteSize(1_001).to_string(), "1001B");
+
+    for text in ["", "12 parsecs", "-1MB"] {
+        assert!(Value::from(text).into_byte_size().is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn test_error_names_key() {
+    let c = make("soon", "512MiB");
+    let err = c.try_deserialize::<Root>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: string \"soon\", expected a duration for key `settings.timeout`"
+    );
+
+    let c = make("1s", "huge");
+    let err = c.try_deserialize::<Root>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: string \"huge\", expected a byte size for key `settings.cache`"
+    );
+
+    let c = make("1s", "-1");
+    let err = c.get::<ByteSize>("settings.cache").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: string \"-1\", expected a byte size for key `settings.cache`"
+    );
+}